@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+// std::io cursors over Segments (or SegmentMuts), either a single one or all the Segments
+// returned by try_split() treated as one contiguous stream
+
+mod segments;
+
+mod segment_reader;
+pub use segment_reader::SegmentReader;
+
+mod segment_writer;
+pub use segment_writer::SegmentWriter;
@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+use std::ops::Deref;
+use std::vec::Vec;
+
+use super::segments::Segments;
+
+#[derive(Debug)]
+pub struct SegmentReader<S> {
+    inner: Segments<S>,
+}
+
+macro_rules! get {
+    ($($t:ty: $le:ident, $be:ident;)*) => {
+        $(
+            pub fn $le(&mut self) -> io::Result<$t> {
+                let mut buf = [0u8; std::mem::size_of::<$t>()];
+                self.read_exact(&mut buf)?;
+                Ok(<$t>::from_le_bytes(buf))
+            }
+
+            pub fn $be(&mut self) -> io::Result<$t> {
+                let mut buf = [0u8; std::mem::size_of::<$t>()];
+                self.read_exact(&mut buf)?;
+                Ok(<$t>::from_be_bytes(buf))
+            }
+        )*
+    };
+}
+
+impl<S: Deref<Target = [u8]>> SegmentReader<S> {
+    pub fn new(segment: S) -> Self {
+        Self::from_vec(vec![segment])
+    }
+
+    // E.g. the result of try_split(), read in order
+    pub fn from_vec(segments: Vec<S>) -> Self {
+        Self {
+            inner: Segments::new(segments),
+        }
+    }
+
+    pub fn into_inner(self) -> Vec<S> {
+        self.inner.into_inner()
+    }
+
+    // Total length of the stream
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn position(&self) -> u64 {
+        self.inner.position()
+    }
+
+    pub fn get_u8(&mut self) -> io::Result<u8> {
+        let mut buf = [0u8; 1];
+        self.read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    pub fn get_i8(&mut self) -> io::Result<i8> {
+        Ok(self.get_u8()? as i8)
+    }
+
+    get! {
+        u16: get_u16_le, get_u16_be;
+        u32: get_u32_le, get_u32_be;
+        u64: get_u64_le, get_u64_be;
+        u128: get_u128_le, get_u128_be;
+        i16: get_i16_le, get_i16_be;
+        i32: get_i32_le, get_i32_be;
+        i64: get_i64_le, get_i64_be;
+        i128: get_i128_le, get_i128_be;
+        f32: get_f32_le, get_f32_be;
+        f64: get_f64_le, get_f64_be;
+    }
+}
+
+impl<S: Deref<Target = [u8]>> Read for SegmentReader<S> {
+    // Reads from one segment at a time, read_exact() and friends take care of the rest
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let current = self.inner.current();
+        let n = current.len().min(buf.len());
+        buf[..n].copy_from_slice(&current[..n]);
+        self.inner.advance(n);
+        Ok(n)
+    }
+}
+
+impl<S: Deref<Target = [u8]>> BufRead for SegmentReader<S> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.inner.current())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.advance(amt.min(self.inner.current().len()))
+    }
+}
+
+impl<S: Deref<Target = [u8]>> Seek for SegmentReader<S> {
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(style)
+    }
+}
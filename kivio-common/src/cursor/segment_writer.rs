@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::io::{self, Seek, SeekFrom, Write};
+use std::ops::DerefMut;
+use std::vec::Vec;
+
+use super::segments::Segments;
+
+// Writes into the existing memory, i.e. the stream can't grow: writing at (or past) the end of the
+// last segment writes nothing and write_all() fails with io::ErrorKind::WriteZero
+#[derive(Debug)]
+pub struct SegmentWriter<S> {
+    inner: Segments<S>,
+}
+
+macro_rules! put {
+    ($($t:ty: $le:ident, $be:ident;)*) => {
+        $(
+            pub fn $le(&mut self, v: $t) -> io::Result<()> {
+                self.write_all(&v.to_le_bytes())
+            }
+
+            pub fn $be(&mut self, v: $t) -> io::Result<()> {
+                self.write_all(&v.to_be_bytes())
+            }
+        )*
+    };
+}
+
+impl<S: DerefMut<Target = [u8]>> SegmentWriter<S> {
+    pub fn new(segment: S) -> Self {
+        Self::from_vec(vec![segment])
+    }
+
+    // E.g. the result of try_split(), written in order
+    pub fn from_vec(segments: Vec<S>) -> Self {
+        Self {
+            inner: Segments::new(segments),
+        }
+    }
+
+    pub fn into_inner(self) -> Vec<S> {
+        self.inner.into_inner()
+    }
+
+    // Total length of the stream
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn position(&self) -> u64 {
+        self.inner.position()
+    }
+
+    pub fn put_u8(&mut self, v: u8) -> io::Result<()> {
+        self.write_all(&[v])
+    }
+
+    pub fn put_i8(&mut self, v: i8) -> io::Result<()> {
+        self.write_all(&[v as u8])
+    }
+
+    put! {
+        u16: put_u16_le, put_u16_be;
+        u32: put_u32_le, put_u32_be;
+        u64: put_u64_le, put_u64_be;
+        u128: put_u128_le, put_u128_be;
+        i16: put_i16_le, put_i16_be;
+        i32: put_i32_le, put_i32_be;
+        i64: put_i64_le, put_i64_be;
+        i128: put_i128_le, put_i128_be;
+        f32: put_f32_le, put_f32_be;
+        f64: put_f64_le, put_f64_be;
+    }
+}
+
+impl<S: DerefMut<Target = [u8]>> Write for SegmentWriter<S> {
+    // Writes into one segment at a time, write_all() and friends take care of the rest
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let current = self.inner.current_mut();
+        let n = current.len().min(buf.len());
+        current[..n].copy_from_slice(&buf[..n]);
+        self.inner.advance(n);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<S: DerefMut<Target = [u8]>> Seek for SegmentWriter<S> {
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(style)
+    }
+}
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::io::{self, SeekFrom};
+use std::ops::{Deref, DerefMut};
+use std::vec::Vec;
+
+// The position bookkeeping shared by SegmentReader and SegmentWriter
+#[derive(Debug)]
+pub(super) struct Segments<S> {
+    segments: Vec<S>,
+    ends: Vec<usize>, // Cumulative, i.e. the stream position right after each segment
+    pos: u64,
+}
+
+impl<S: Deref<Target = [u8]>> Segments<S> {
+    pub(super) fn new(segments: Vec<S>) -> Self {
+        let ends = segments
+            .iter()
+            .scan(0, |end, s| {
+                *end += s.len();
+                Some(*end)
+            })
+            .collect();
+        Self {
+            segments,
+            ends,
+            pos: 0,
+        }
+    }
+
+    pub(super) fn into_inner(self) -> Vec<S> {
+        self.segments
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    pub(super) fn position(&self) -> u64 {
+        self.pos
+    }
+
+    // Index of the segment at the current position and the offset within it, None at (or past)
+    // the end of the stream
+    fn locate(&self) -> Option<(usize, usize)> {
+        if self.pos >= self.len() as u64 {
+            return None;
+        }
+        let pos = self.pos as usize;
+        // Skips empty segments as well
+        let i = self.ends.partition_point(|&end| end <= pos);
+        let start = if i == 0 { 0 } else { self.ends[i - 1] };
+        Some((i, pos - start))
+    }
+
+    // The rest of the segment at the current position
+    pub(super) fn current(&self) -> &[u8] {
+        match self.locate() {
+            Some((i, offset)) => &self.segments[i][offset..],
+            None => &[],
+        }
+    }
+
+    pub(super) fn advance(&mut self, n: usize) {
+        self.pos += n as u64;
+    }
+
+    pub(super) fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match style {
+            SeekFrom::Start(n) => {
+                self.pos = n;
+                return Ok(n);
+            }
+            SeekFrom::End(n) => (self.len() as u64, n),
+            SeekFrom::Current(n) => (self.pos, n),
+        };
+        match base.checked_add_signed(offset) {
+            Some(n) => {
+                self.pos = n;
+                Ok(n)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl<S: DerefMut<Target = [u8]>> Segments<S> {
+    pub(super) fn current_mut(&mut self) -> &mut [u8] {
+        match self.locate() {
+            Some((i, offset)) => &mut self.segments[i][offset..],
+            None => &mut [],
+        }
+    }
+}
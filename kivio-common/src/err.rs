@@ -41,4 +41,68 @@ pub enum Error {
     #[error("IoVec elements overlap, possibly due to given outer length ({outer_len})")]
     OverlappingIoVec { outer_len: usize },
 
+    #[error("Alignment ({align}) is not a power of two")]
+    InvalidAlignment { align: usize },
+
+    #[error(
+        "IoVec range ({start}:{end}) is not aligned to block size ({block_size}) for given outer \
+             length ({outer_len})"
+    )]
+    MisalignedIoVec {
+        start: i64,
+        end: i64,
+        outer_len: usize,
+        block_size: usize,
+    },
+
+    #[error("Buffer can't be viewed as [{type_name}], it is not aligned to ({align})")]
+    MisalignedTypedView { type_name: String, align: usize },
+
+    #[error(
+        "Buffer of length ({len}) can't be viewed as [{type_name}], the length is not a multiple \
+             of ({size})"
+    )]
+    InvalidTypedViewLength {
+        type_name: String,
+        size: usize,
+        len: usize,
+    },
+
+    #[error("Element range ({start}:{end}) overflows for elements of size ({size})")]
+    ElementRangeOverflow {
+        start: usize,
+        end: usize,
+        size: usize,
+    },
+
+    #[error("Buffer is not aligned to block size ({block_size})")]
+    MisalignedBuffer { block_size: usize },
+
+    #[error(
+        "Canary after end of buffer of length ({len}) was overwritten, first corrupted byte is at \
+             offset ({offset})"
+    )]
+    CorruptedCanary { offset: usize, len: usize },
+
+    #[error("Byte range ({start}:{end}) is locked")]
+    RangeLocked { start: usize, end: usize },
+
+    #[error("Operation {operation} is not supported: {reason}")]
+    UnsupportedOperation { operation: String, reason: String },
+
+    #[error("System call {call} failed: {source}")]
+    SystemCallFailed {
+        call: String,
+        source: std::io::Error,
+    },
+}
+
+impl Error {
+    // To be called right after a failed system call (i.e. before errno can change)
+    pub(crate) fn last_os_error(call: &str) -> Self {
+        Self::SystemCallFailed {
+            call: call.to_string(),
+            source: std::io::Error::last_os_error(),
+        }
+    }
 }
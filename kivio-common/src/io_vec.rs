@@ -6,6 +6,6 @@ pub use byte_pos::BytePos;
 mod byte_range;
 pub use byte_range::ByteRange;
 
+#[allow(clippy::module_inception)]
 mod io_vec;
 pub use io_vec::IoVec;
-
@@ -16,7 +16,7 @@ impl BytePos {
         !self.is_absolute()
     }
 
-    pub(crate) fn to_absolute(&self, outer_len: usize) -> Result<BytePos, Error> {
+    pub(crate) fn to_absolute(self, outer_len: usize) -> Result<BytePos, Error> {
         if self.is_absolute() {
             if (self.0 as usize) > outer_len {
                 return Err(Error::InvalidBytePos {
@@ -44,20 +44,18 @@ impl BytePos {
         )
     }
 
-    fn to_usize_max_outer_len(&self) -> usize {
+    fn to_usize_max_outer_len(self) -> usize {
         if self.is_absolute() {
             self.0 as usize
         } else {
             usize::MAX - ((-self.0) as usize)
         }
     }
-
 }
 
 impl PartialOrd for BytePos {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.to_usize_max_outer_len()
-            .partial_cmp(&other.to_usize_max_outer_len())
+        Some(self.cmp(other))
     }
 }
 
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 
 use std::cmp::max;
+use std::mem::size_of;
 
 use crate::err::Error;
 
@@ -35,8 +36,18 @@ impl ByteRange {
         )
     }
 
+    // Range of elements of type T rather than bytes, e.g. for use with Segment::as_slice()
+    pub fn new_elements<T>(start: usize, end: usize) -> Result<Self, Error> {
+        let size = size_of::<T>();
+        let to_i64 = |n: usize| n.checked_mul(size).and_then(|n| i64::try_from(n).ok());
+        match (to_i64(start), to_i64(end)) {
+            (Some(byte_start), Some(byte_end)) => Self::new_i64(byte_start, byte_end),
+            _ => Err(Error::ElementRangeOverflow { start, end, size }),
+        }
+    }
+
     pub fn is_absolute(&self) -> bool {
-        return self.start.is_absolute() && self.end.is_absolute();
+        self.start.is_absolute() && self.end.is_absolute()
     }
 
     pub fn absolute_start_end(&self, outer_len: usize) -> Result<(BytePos, BytePos), Error> {
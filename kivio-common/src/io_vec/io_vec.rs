@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 
+use std::mem::size_of;
 use std::ops::Deref;
 use std::vec::Vec;
 
@@ -55,6 +56,17 @@ impl IoVec {
         }
     }
 
+    // Like from_chunk_size() but in elements of type T rather than bytes. Panics if the lengths in
+    // bytes overflow, which can't happen for elements of memory that exists.
+    pub fn from_element_chunk_size<T>(total_elements: usize, chunk_elements: usize) -> Self {
+        let total_len = total_elements.checked_mul(size_of::<T>());
+        let chunk_size = chunk_elements.checked_mul(size_of::<T>());
+        Self::from_chunk_size(
+            total_len.expect("Total length in bytes overflows"),
+            chunk_size.expect("Chunk size in bytes overflows"),
+        )
+    }
+
     pub fn from_vec_byte_range(byte_ranges: Vec<ByteRange>) -> Self {
         Self {
             tallest_range: helper::get_tallest_range(&byte_ranges),
@@ -80,6 +92,33 @@ impl IoVec {
             None => Ok(helper::get_overlapping(&self.byte_ranges, outer_len)?),
         }
     }
+
+    // Checks that all ranges start and end at a multiple of block_size given outer_len (e.g. to
+    // satisfy the requirements of O_DIRECT)
+    pub fn check_aligned(&self, outer_len: usize, block_size: usize) -> Result<(), Error> {
+        if !block_size.is_power_of_two() {
+            return Err(Error::InvalidAlignment { align: block_size });
+        }
+        if outer_len < self.min_outer_len() {
+            return Err(Error::InvalidIoVec {
+                start: self.tallest_range.start.0,
+                end: self.tallest_range.end.0,
+                outer_len,
+            });
+        }
+        for r in self.byte_ranges.iter() {
+            let (offset, len) = r.to_offset_len(outer_len)?;
+            if !offset.is_multiple_of(block_size) || !len.is_multiple_of(block_size) {
+                return Err(Error::MisalignedIoVec {
+                    start: r.start.0,
+                    end: r.end.0,
+                    outer_len,
+                    block_size,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Deref for IoVec {
@@ -125,12 +164,12 @@ pub(super) mod helper {
         }
     }
 
-    pub(crate) fn get_tallest_range(byte_ranges: &Vec<ByteRange>) -> ByteRange {
+    pub(crate) fn get_tallest_range(byte_ranges: &[ByteRange]) -> ByteRange {
         match byte_ranges
             .iter()
             .max_by(|a, b| a.min_outer_len().cmp(&b.min_outer_len()))
         {
-            Some(br) => br.clone(),
+            Some(br) => *br,
             None => ByteRange::new_i64(0, 0).unwrap(),
         }
     }
@@ -147,7 +186,7 @@ pub(super) mod helper {
                 return true;
             }
         }
-        return false;
+        false
     }
 
     pub(crate) fn get_cached_overlapping(byte_ranges: &Vec<ByteRange>) -> Option<bool> {
@@ -195,11 +234,9 @@ pub(super) mod helper {
         let mut potential_r_start = len_a;
         if s[len_a].start.is_absolute() && s[len_a].end.is_relative() {
             let pos_m = &len_a;
-            if pos_m > &0 {
-                if s[pos_m - 1].end > s[*pos_m].start {
-                    // m overlaps with a< -1>
-                    return Some(true);
-                }
+            if pos_m > &0 && s[pos_m - 1].end > s[*pos_m].start {
+                // m overlaps with a< -1>
+                return Some(true);
             }
             if *pos_m == s.len() {
                 // No [r<i>] => if we didn't overlap yet, we never will
@@ -224,15 +261,15 @@ pub(super) mod helper {
             .position(|&x| x.start.is_absolute() || x.end.is_absolute())
         {
             // Tail after potential_r_start are all relative and might overlap
-            None => return Some(subrange_has_overlap(&s[potential_r_start..])),
+            None => Some(subrange_has_overlap(&s[potential_r_start..])),
 
             // Tail is not all relative -> can't tell without outer_len
-            Some(_l) => return None,
-        };
+            Some(_l) => None,
+        }
     }
 
     pub(crate) fn get_overlapping(
-        byte_ranges: &Vec<ByteRange>,
+        byte_ranges: &[ByteRange],
         outer_len: usize,
     ) -> Result<bool, Error> {
         let rv: Result<Vec<_>, _> = byte_ranges
@@ -2,10 +2,20 @@
 
 pub mod traits;
 pub use traits::{
-    FdSegment, FdSegmentMut, Handle, HandleMut, Segment, SegmentMut, VmemSegment, VmemSegmentMut,
+    BackendKind, FdSegment, FdSegmentMut, Handle, HandleMut, Segment, SegmentMut, VmemSegment,
+    VmemSegmentMut, WeakHandle,
 };
 
 pub mod err;
 pub mod io_vec;
 
+mod pod;
+pub use pod::Pod;
+
 pub mod vmem;
+
+pub mod memfd;
+
+pub mod cursor;
+
+pub mod vectored;
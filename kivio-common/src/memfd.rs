@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+// memfd backed memory that can be accessed both through pointers and a file descriptor (i.e. the
+// Segments implement both VmemSegment and FdSegment) and can be passed between processes. The
+// types wrap their vmem counterparts, which they can be converted from and into.
+
+mod handle;
+pub use handle::Handle;
+
+mod handle_mut;
+pub use handle_mut::{HandleMut, Unsealed};
+
+mod segment;
+pub use segment::Segment;
+
+mod segment_mut;
+pub use segment_mut::SegmentMut;
+
+pub use crate::vmem::Memfd;
+
+pub(super) mod helper {
+
+    use std::os::unix::io::RawFd;
+
+    use crate::vmem::Vmem;
+
+    pub(crate) fn is_memfd(vmem: &Vmem) -> bool {
+        matches!(vmem, Vmem::Memfd(_))
+    }
+
+    pub(crate) fn memfd(vmem: &Vmem) -> &super::Memfd {
+        match vmem {
+            Vmem::Memfd(ref m) => m,
+            _ => unreachable!("memfd types are always backed by a vmem::Memfd"),
+        }
+    }
+
+    pub(crate) fn fd_offset_len(vmem: &Vmem, ptr: *const u8, len: usize) -> (RawFd, usize, usize) {
+        let (base_ptr, _) = vmem.mut_ptr_len();
+        (memfd(vmem).fd(), ptr as usize - base_ptr as usize, len)
+    }
+}
@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::any::type_name;
+use std::convert::{From, TryFrom};
+use std::io;
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::ptr;
+
+use crate::err::Error;
+use crate::traits;
+use crate::vmem::{self, Memfd, Vmem};
+
+use super::helper::{is_memfd, memfd};
+use super::{HandleMut, Segment};
+
+#[derive(Debug, Clone)]
+pub struct Handle {
+    pub(super) inner: vmem::Handle,
+}
+
+impl Handle {
+    pub fn fd(&self) -> RawFd {
+        memfd(self.inner.vmem()).fd()
+    }
+
+    // True if nobody (including the sending process) can modify the contents anymore
+    pub fn is_sealed(&self) -> bool {
+        memfd(self.inner.vmem()).is_sealed()
+    }
+
+    // Passes the file descriptor to the process at the other end of the socket (SCM_RIGHTS), which
+    // can reconstruct a read-only Handle using recv(). The received Handle uses an open file
+    // description of its own (see vmem::Memfd::from_fd()), so range locks contend across both ends.
+    pub fn send(&self, socket: &UnixStream) -> Result<(), Error> {
+        let mut byte = [0u8; 1];
+        let mut cmsg_buf = helper::cmsg_buf();
+        let mut msg = helper::msghdr(&mut cmsg_buf);
+        let mut iov = libc::iovec {
+            iov_base: byte.as_mut_ptr() as *mut libc::c_void,
+            iov_len: byte.len(),
+        };
+        msg.msg_iov = &mut iov;
+        unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(mem::size_of::<RawFd>() as u32) as _;
+            ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut RawFd, self.fd());
+        }
+        if unsafe { libc::sendmsg(socket.as_raw_fd(), &msg, 0) } < 0 {
+            return Err(Error::last_os_error("sendmsg"));
+        }
+        Ok(())
+    }
+
+    pub fn recv(socket: &UnixStream) -> Result<Self, Error> {
+        let mut byte = [0u8; 1];
+        let mut cmsg_buf = helper::cmsg_buf();
+        let mut msg = helper::msghdr(&mut cmsg_buf);
+        let mut iov = libc::iovec {
+            iov_base: byte.as_mut_ptr() as *mut libc::c_void,
+            iov_len: byte.len(),
+        };
+        msg.msg_iov = &mut iov;
+        let n = unsafe { libc::recvmsg(socket.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC) };
+        if n < 0 {
+            return Err(Error::last_os_error("recvmsg"));
+        }
+        let fd = unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            if cmsg.is_null()
+                || (*cmsg).cmsg_level != libc::SOL_SOCKET
+                || (*cmsg).cmsg_type != libc::SCM_RIGHTS
+            {
+                return Err(Error::SystemCallFailed {
+                    call: "recvmsg".to_string(),
+                    source: io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "No file descriptor received",
+                    ),
+                });
+            }
+            OwnedFd::from_raw_fd(ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const RawFd))
+        };
+        Ok(Self {
+            inner: vmem::Handle::from_vmem(Vmem::from_memfd(Memfd::from_fd(fd)?)),
+        })
+    }
+}
+
+impl traits::Handle for Handle {
+    type HandleMut = HandleMut;
+    type Segment = Segment;
+
+    fn from_handle_mut(handle_mut: Self::HandleMut) -> Self {
+        Self {
+            inner: traits::Handle::from_handle_mut(handle_mut.inner),
+        }
+    }
+
+    fn from_segment(segment: Self::Segment) -> Self {
+        Self {
+            inner: traits::Handle::from_segment(segment.inner),
+        }
+    }
+
+    fn len(&self) -> usize {
+        traits::Handle::len(&self.inner)
+    }
+
+    fn capacity(&self) -> usize {
+        traits::Handle::capacity(&self.inner)
+    }
+
+    fn backend_kind(&self) -> traits::BackendKind {
+        traits::Handle::backend_kind(&self.inner)
+    }
+
+    fn outstanding_refs(&self) -> usize {
+        traits::Handle::outstanding_refs(&self.inner)
+    }
+}
+
+impl From<HandleMut> for Handle {
+    fn from(item: HandleMut) -> Self {
+        traits::Handle::from_handle_mut(item)
+    }
+}
+
+impl From<Segment> for Handle {
+    fn from(item: Segment) -> Self {
+        traits::Handle::from_segment(item)
+    }
+}
+
+impl TryFrom<vmem::Handle> for Handle {
+    type Error = (Error, vmem::Handle);
+
+    fn try_from(item: vmem::Handle) -> Result<Self, Self::Error> {
+        if is_memfd(item.vmem()) {
+            Ok(Self { inner: item })
+        } else {
+            Err((
+                Error::ConversionFailed {
+                    from_type: type_name::<vmem::Handle>().to_string(),
+                    to_type: type_name::<Self>().to_string(),
+                    reason: "vmem::Handle is not backed by a vmem::Memfd".to_string(),
+                },
+                item,
+            ))
+        }
+    }
+}
+
+impl From<Handle> for vmem::Handle {
+    fn from(item: Handle) -> Self {
+        item.inner
+    }
+}
+
+mod helper {
+
+    use std::mem;
+    use std::os::unix::io::RawFd;
+
+    // Room for exactly one file descriptor, u64 ensures the alignment required for cmsghdr
+    pub(super) fn cmsg_buf() -> Vec<u64> {
+        let space = unsafe { libc::CMSG_SPACE(mem::size_of::<RawFd>() as u32) } as usize;
+        vec![0u64; space.div_ceil(mem::size_of::<u64>())]
+    }
+
+    // msg_iov has to be set by the caller
+    pub(super) fn msghdr(cmsg_buf: &mut [u64]) -> libc::msghdr {
+        let mut msg = unsafe { mem::zeroed::<libc::msghdr>() };
+        msg.msg_iovlen = 1;
+        msg.msg_control = cmsg_buf.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = unsafe { libc::CMSG_SPACE(mem::size_of::<RawFd>() as u32) } as _;
+        msg
+    }
+}
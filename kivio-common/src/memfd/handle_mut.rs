@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::any::type_name;
+use std::convert::{From, TryFrom};
+
+use crate::err::Error;
+use crate::traits;
+use crate::vmem::{self, Vmem};
+
+use super::helper::is_memfd;
+use super::{Handle, SegmentMut};
+
+#[derive(Debug)]
+pub struct HandleMut {
+    pub(super) inner: vmem::HandleMut,
+}
+
+impl HandleMut {
+    pub fn new(name: &str, len: usize) -> Result<Self, Error> {
+        // A new memfd is always writable
+        let inner =
+            vmem::HandleMut::try_from_vmem(Vmem::new_memfd(name, len)?).map_err(|(e, _)| e)?;
+        Ok(Self { inner })
+    }
+
+    // Seals the memory against any further modifications (by anyone, including processes the
+    // Handle is sent to). Fails if there are other writable mappings of the memfd, in which case
+    // the HandleMut is handed back still writable, unless even mapping the memory writable again
+    // fails: then it is handed back as a (read-only, but unsealed) Handle.
+    pub fn seal(mut self) -> Result<Handle, (Error, Unsealed)> {
+        let r = match self.inner.vmem_mut() {
+            Vmem::Memfd(ref mut m) => m.seal(),
+            _ => unreachable!("memfd types are always backed by a vmem::Memfd"),
+        };
+        let into_handle = |inner| Handle {
+            inner: traits::Handle::from_handle_mut(inner),
+        };
+        match r {
+            Ok(()) => Ok(into_handle(self.inner)),
+            Err(e) if self.inner.vmem().is_read_only() => {
+                Err((e, Unsealed::Handle(into_handle(self.inner))))
+            }
+            Err(e) => Err((e, Unsealed::HandleMut(self))),
+        }
+    }
+}
+
+// What HandleMut::seal() hands back on failure
+#[derive(Debug)]
+pub enum Unsealed {
+    // Still writable
+    HandleMut(HandleMut),
+    // The memory is mapped read-only and could not be mapped writable again
+    Handle(Handle),
+}
+
+impl traits::HandleMut for HandleMut {
+    type Handle = Handle;
+    type SegmentMut = SegmentMut;
+
+    fn try_from_handle(handle: Self::Handle) -> Result<Self, (Error, Self::Handle)> {
+        match vmem::HandleMut::try_from(handle.inner) {
+            Ok(inner) => Ok(Self { inner }),
+            Err((e, inner)) => Err((e, Self::Handle { inner })),
+        }
+    }
+
+    fn try_from_segment_mut(
+        segment_mut: Self::SegmentMut,
+    ) -> Result<Self, (Error, Self::SegmentMut)> {
+        match vmem::HandleMut::try_from(segment_mut.inner) {
+            Ok(inner) => Ok(Self { inner }),
+            Err((e, inner)) => Err((e, Self::SegmentMut { inner })),
+        }
+    }
+
+    fn len(&self) -> usize {
+        traits::HandleMut::len(&self.inner)
+    }
+
+    fn capacity(&self) -> usize {
+        traits::HandleMut::capacity(&self.inner)
+    }
+
+    fn backend_kind(&self) -> traits::BackendKind {
+        traits::HandleMut::backend_kind(&self.inner)
+    }
+
+    fn outstanding_refs(&self) -> usize {
+        traits::HandleMut::outstanding_refs(&self.inner)
+    }
+}
+
+impl TryFrom<Handle> for HandleMut {
+    type Error = (Error, Handle);
+
+    fn try_from(item: Handle) -> Result<Self, Self::Error> {
+        traits::HandleMut::try_from_handle(item)
+    }
+}
+
+impl TryFrom<SegmentMut> for HandleMut {
+    type Error = (Error, SegmentMut);
+
+    fn try_from(item: SegmentMut) -> Result<Self, Self::Error> {
+        traits::HandleMut::try_from_segment_mut(item)
+    }
+}
+
+impl TryFrom<vmem::HandleMut> for HandleMut {
+    type Error = (Error, vmem::HandleMut);
+
+    fn try_from(item: vmem::HandleMut) -> Result<Self, Self::Error> {
+        if is_memfd(item.vmem()) {
+            Ok(Self { inner: item })
+        } else {
+            Err((
+                Error::ConversionFailed {
+                    from_type: type_name::<vmem::HandleMut>().to_string(),
+                    to_type: type_name::<Self>().to_string(),
+                    reason: "vmem::HandleMut is not backed by a vmem::Memfd".to_string(),
+                },
+                item,
+            ))
+        }
+    }
+}
+
+impl From<HandleMut> for vmem::HandleMut {
+    fn from(item: HandleMut) -> Self {
+        item.inner
+    }
+}
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::convert::From;
+use std::ops::Deref;
+use std::os::unix::io::RawFd;
+use std::vec::Vec;
+
+use crate::err::Error;
+use crate::io_vec::{ByteRange, IoVec};
+use crate::pod::Pod;
+use crate::traits;
+use crate::vmem::{self, Advice, LockMode, RangeLockGuard};
+
+use super::helper::fd_offset_len;
+use super::Handle;
+
+#[derive(Debug, Clone)]
+pub struct Segment {
+    pub(super) inner: vmem::Segment,
+}
+
+impl Segment {
+    pub fn as_slice<T: Pod>(&self) -> Result<&[T], Error> {
+        self.inner.as_slice()
+    }
+
+    // Like vmem::Segment::lock_range(), but additionally takes an open file description lock on the
+    // memfd so that other processes contend as well (see fcntl(2) for when they do)
+    pub fn lock_range(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        self.lock(range, mode, true)
+    }
+
+    pub fn try_lock_range(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        self.lock(range, mode, false)
+    }
+
+    fn lock(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+        blocking: bool,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        let (fd, _, _) = traits::FdSegment::fd_offset_len(self);
+        self.inner.lock(range, mode, blocking, Some(fd))
+    }
+
+    pub fn advise(&self, advice: Advice) -> Result<(), Error> {
+        self.inner.advise(advice)
+    }
+
+    pub fn prefetch(&self, io_vec: &IoVec) -> Result<(), Error> {
+        self.inner.prefetch(io_vec)
+    }
+}
+
+impl traits::Segment for Segment {
+    type Handle = Handle;
+
+    fn from_handle(handle: Self::Handle) -> Self {
+        Self {
+            inner: traits::Segment::from_handle(handle.inner),
+        }
+    }
+
+    fn try_split(self, io_vec: &IoVec) -> Result<Vec<Self>, (Error, Self)> {
+        match traits::Segment::try_split(self.inner, io_vec) {
+            Ok(v) => Ok(v.into_iter().map(|inner| Self { inner }).collect()),
+            Err((e, inner)) => Err((e, Self { inner })),
+        }
+    }
+}
+
+impl traits::VmemSegment for Segment {
+    fn ptr_len(&self) -> (*const u8, usize) {
+        traits::VmemSegment::ptr_len(&self.inner)
+    }
+}
+
+impl traits::FdSegment for Segment {
+    fn fd_offset_len(&self) -> (RawFd, usize, usize) {
+        let (ptr, len) = traits::VmemSegment::ptr_len(&self.inner);
+        fd_offset_len(self.inner.vmem(), ptr, len)
+    }
+}
+
+impl From<Handle> for Segment {
+    fn from(item: Handle) -> Self {
+        traits::Segment::from_handle(item)
+    }
+}
+
+impl From<Segment> for vmem::Segment {
+    fn from(item: Segment) -> Self {
+        item.inner
+    }
+}
+
+impl Deref for Segment {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        &self.inner
+    }
+}
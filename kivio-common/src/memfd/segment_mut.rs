@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::convert::{From, TryFrom};
+use std::ops::{Deref, DerefMut};
+use std::os::unix::io::RawFd;
+use std::vec::Vec;
+
+use crate::err::Error;
+use crate::io_vec::{ByteRange, IoVec};
+use crate::pod::Pod;
+use crate::traits;
+use crate::vmem::{self, Advice, LockMode, RangeLockGuard, RangeLockGuardMut};
+
+use super::helper::fd_offset_len;
+use super::HandleMut;
+
+#[derive(Debug)]
+pub struct SegmentMut {
+    pub(super) inner: vmem::SegmentMut,
+}
+
+impl SegmentMut {
+    pub fn as_slice<T: Pod>(&self) -> Result<&[T], Error> {
+        self.inner.as_slice()
+    }
+
+    pub fn as_mut_slice<T: Pod>(&mut self) -> Result<&mut [T], Error> {
+        self.inner.as_mut_slice()
+    }
+
+    // Like vmem::SegmentMut::lock_range(), but additionally takes an open file description lock on
+    // the memfd so that other processes contend as well (see fcntl(2) for when they do)
+    pub fn lock_range(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        self.lock(range, mode, true)
+    }
+
+    pub fn try_lock_range(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        self.lock(range, mode, false)
+    }
+
+    pub fn lock_range_mut(&mut self, range: &ByteRange) -> Result<RangeLockGuardMut<'_>, Error> {
+        let (fd, _, _) = traits::FdSegmentMut::fd_offset_len(self);
+        self.inner.lock_mut(range, true, Some(fd))
+    }
+
+    pub fn try_lock_range_mut(
+        &mut self,
+        range: &ByteRange,
+    ) -> Result<RangeLockGuardMut<'_>, Error> {
+        let (fd, _, _) = traits::FdSegmentMut::fd_offset_len(self);
+        self.inner.lock_mut(range, false, Some(fd))
+    }
+
+    fn lock(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+        blocking: bool,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        let (fd, _, _) = traits::FdSegmentMut::fd_offset_len(self);
+        self.inner.lock(range, mode, blocking, Some(fd))
+    }
+
+    pub fn advise(&self, advice: Advice) -> Result<(), Error> {
+        self.inner.advise(advice)
+    }
+
+    pub fn discard(&mut self, advice: Advice) -> Result<(), Error> {
+        self.inner.discard(advice)
+    }
+
+    pub fn prefetch(&self, io_vec: &IoVec) -> Result<(), Error> {
+        self.inner.prefetch(io_vec)
+    }
+}
+
+impl traits::SegmentMut for SegmentMut {
+    type HandleMut = HandleMut;
+
+    fn from_handle_mut(handle_mut: Self::HandleMut) -> Self {
+        Self {
+            inner: traits::SegmentMut::from_handle_mut(handle_mut.inner),
+        }
+    }
+
+    fn try_from_vec_segment_mut(v: Vec<Self>) -> Result<Self, (Error, Vec<Self>)> {
+        let inner_v = v.into_iter().map(|s| s.inner).collect();
+        match traits::SegmentMut::try_from_vec_segment_mut(inner_v) {
+            Ok(inner) => Ok(Self { inner }),
+            Err((e, inner_v)) => {
+                Err((e, inner_v.into_iter().map(|inner| Self { inner }).collect()))
+            }
+        }
+    }
+
+    fn try_split(self, io_vec: &IoVec) -> Result<Vec<Self>, (Error, Self)> {
+        match traits::SegmentMut::try_split(self.inner, io_vec) {
+            Ok(v) => Ok(v.into_iter().map(|inner| Self { inner }).collect()),
+            Err((e, inner)) => Err((e, Self { inner })),
+        }
+    }
+}
+
+impl traits::VmemSegmentMut for SegmentMut {
+    fn mut_ptr_len(&self) -> (*mut u8, usize) {
+        traits::VmemSegmentMut::mut_ptr_len(&self.inner)
+    }
+}
+
+impl traits::FdSegmentMut for SegmentMut {
+    fn fd_offset_len(&self) -> (RawFd, usize, usize) {
+        let (mut_ptr, len) = traits::VmemSegmentMut::mut_ptr_len(&self.inner);
+        fd_offset_len(self.inner.vmem(), mut_ptr, len)
+    }
+}
+
+impl From<HandleMut> for SegmentMut {
+    fn from(item: HandleMut) -> Self {
+        traits::SegmentMut::from_handle_mut(item)
+    }
+}
+
+impl TryFrom<Vec<SegmentMut>> for SegmentMut {
+    type Error = (Error, Vec<Self>);
+
+    fn try_from(item: Vec<Self>) -> Result<Self, Self::Error> {
+        traits::SegmentMut::try_from_vec_segment_mut(item)
+    }
+}
+
+impl From<SegmentMut> for vmem::SegmentMut {
+    fn from(item: SegmentMut) -> Self {
+        item.inner
+    }
+}
+
+impl Deref for SegmentMut {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl DerefMut for SegmentMut {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.inner
+    }
+}
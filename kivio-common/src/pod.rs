@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::any::type_name;
+use std::mem::{align_of, size_of};
+use std::slice;
+
+use crate::err::Error;
+
+// Plain old data: types for which every bit pattern is a valid value, so that any (suitably
+// aligned) memory can be viewed as a slice of them.
+
+/// # Safety
+///
+/// Implementors must be `Copy`, have no padding bytes, no invalid bit patterns (like bool or
+/// char), no pointers or references and a non-zero size.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+// Checks that ptr and len can be viewed as a [T]
+pub(crate) fn check_view<T: Pod>(ptr: *const u8, len: usize) -> Result<usize, Error> {
+    if !(ptr as usize).is_multiple_of(align_of::<T>()) {
+        return Err(Error::MisalignedTypedView {
+            type_name: type_name::<T>().to_string(),
+            align: align_of::<T>(),
+        });
+    }
+    if size_of::<T>() == 0 || !len.is_multiple_of(size_of::<T>()) {
+        return Err(Error::InvalidTypedViewLength {
+            type_name: type_name::<T>().to_string(),
+            size: size_of::<T>(),
+            len,
+        });
+    }
+    Ok(len / size_of::<T>())
+}
+
+// ptr must be valid for reads of len bytes for the lifetime 'a
+pub(crate) unsafe fn view<'a, T: Pod>(ptr: *const u8, len: usize) -> Result<&'a [T], Error> {
+    let n = check_view::<T>(ptr, len)?;
+    Ok(slice::from_raw_parts(ptr as *const T, n))
+}
+
+// mut_ptr must be valid for reads and writes of len bytes and not be accessed through any other
+// pointer for the lifetime 'a
+pub(crate) unsafe fn view_mut<'a, T: Pod>(
+    mut_ptr: *mut u8,
+    len: usize,
+) -> Result<&'a mut [T], Error> {
+    let n = check_view::<T>(mut_ptr, len)?;
+    Ok(slice::from_raw_parts_mut(mut_ptr as *mut T, n))
+}
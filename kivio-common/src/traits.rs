@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 
 mod handle;
-pub use handle::{Handle, HandleMut};
+pub use handle::{BackendKind, Handle, HandleMut, WeakHandle};
 
 mod segment;
 pub use segment::{FdSegment, FdSegmentMut, Segment, SegmentMut, VmemSegment, VmemSegmentMut};
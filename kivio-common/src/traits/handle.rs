@@ -4,6 +4,21 @@ use crate::err::Error;
 
 use super::segment;
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum BackendKind {
+    VecU8,
+    BoxU8,
+    StaticU8,
+    Bytes,
+    BytesMut,
+    Aligned,
+    AnonMmap,
+    Memfd,
+    Lazy,
+    Foreign,
+}
+
 pub trait Handle {
     type HandleMut: HandleMut;
     type Segment: segment::Segment;
@@ -11,6 +26,20 @@ pub trait Handle {
     fn from_handle_mut(handle_mut: Self::HandleMut) -> Self;
 
     fn from_segment(segment: Self::Segment) -> Self;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Number of bytes actually reserved for the resource (>= len())
+    fn capacity(&self) -> usize;
+
+    fn backend_kind(&self) -> BackendKind;
+
+    // Number of other Handles and Segments referencing the same resource
+    fn outstanding_refs(&self) -> usize;
 }
 
 pub trait HandleMut {
@@ -26,4 +55,29 @@ pub trait HandleMut {
     ) -> Result<Self, (Error, Self::SegmentMut)>
     where
         Self: Sized;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Number of bytes actually reserved for the resource (>= len())
+    fn capacity(&self) -> usize;
+
+    fn backend_kind(&self) -> BackendKind;
+
+    // Number of other Handles and Segments referencing the same resource
+    fn outstanding_refs(&self) -> usize;
+}
+
+pub trait WeakHandle {
+    type Handle: Handle;
+
+    fn from_handle(handle: &Self::Handle) -> Self;
+
+    fn upgrade(&self) -> Option<Self::Handle>;
+
+    // Number of Handles and Segments currently keeping the resource alive
+    fn outstanding_refs(&self) -> usize;
 }
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+// Scatter/gather IO (readv(2)/writev(2) via Read::read_vectored() and Write::write_vectored())
+// directly into or out of the Segments returned by try_split()
+
+use std::io::{self, IoSlice, IoSliceMut, Read, Write};
+use std::ops::{Deref, DerefMut};
+use std::vec::Vec;
+
+use crate::err::Error;
+use crate::io_vec::{ByteRange, IoVec};
+
+pub fn io_slices<S: Deref<Target = [u8]>>(segments: &[S]) -> Vec<IoSlice<'_>> {
+    segments.iter().map(|s| IoSlice::new(s)).collect()
+}
+
+pub fn io_slices_mut<S: DerefMut<Target = [u8]>>(segments: &mut [S]) -> Vec<IoSliceMut<'_>> {
+    segments.iter_mut().map(|s| IoSliceMut::new(s)).collect()
+}
+
+// Keeps track of how many bytes of a list of Segments have been transferred so far, across short
+// reads and writes
+#[derive(Debug, Clone)]
+pub struct Progress {
+    lens: Vec<usize>,
+    done: usize,
+}
+
+impl Progress {
+    pub fn new<S: Deref<Target = [u8]>>(segments: &[S]) -> Self {
+        Self {
+            lens: segments.iter().map(|s| s.len()).collect(),
+            done: 0,
+        }
+    }
+
+    // Total number of bytes transferred
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    pub fn total(&self) -> usize {
+        self.lens.iter().sum()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.done == self.total()
+    }
+
+    // To be called with the return value of every read_vectored() or write_vectored() (unless
+    // read_from() or write_to() are used)
+    pub fn advance(&mut self, n: usize) {
+        self.done = (self.done + n).min(self.total());
+    }
+
+    // Number of Segments that have been transferred completely
+    pub fn completed(&self) -> usize {
+        let mut remaining = self.done;
+        self.lens
+            .iter()
+            .take_while(|&&len| {
+                let complete = remaining >= len;
+                remaining = remaining.saturating_sub(len);
+                complete
+            })
+            .count()
+    }
+
+    // The (absolute) ranges of io_vec, the IoVec the Segments were split with, that have been
+    // transferred. The last one is truncated in case a Segment was only partially transferred.
+    pub fn completed_ranges(
+        &self,
+        io_vec: &IoVec,
+        outer_len: usize,
+    ) -> Result<Vec<ByteRange>, Error> {
+        let mut remaining = self.done;
+        let mut ranges = Vec::new();
+        for r in io_vec.iter() {
+            if remaining == 0 {
+                break;
+            }
+            let (offset, len) = r.to_offset_len(outer_len)?;
+            let n = len.min(remaining);
+            ranges.push(ByteRange::new_usize(offset, offset + n)?);
+            remaining -= n;
+        }
+        Ok(ranges)
+    }
+
+    // IoSlices for the part of segments that hasn't been transferred yet
+    pub fn io_slices<'a, S: Deref<Target = [u8]>>(&self, segments: &'a [S]) -> Vec<IoSlice<'a>> {
+        let mut skip = self.done;
+        segments
+            .iter()
+            .filter_map(|s| {
+                let n = skip.min(s.len());
+                skip -= n;
+                (n < s.len()).then(|| IoSlice::new(&s[n..]))
+            })
+            .collect()
+    }
+
+    pub fn io_slices_mut<'a, S: DerefMut<Target = [u8]>>(
+        &self,
+        segments: &'a mut [S],
+    ) -> Vec<IoSliceMut<'a>> {
+        let mut skip = self.done;
+        segments
+            .iter_mut()
+            .filter_map(|s| {
+                let n = skip.min(s.len());
+                skip -= n;
+                (n < s.len()).then(|| IoSliceMut::new(&mut s[n..]))
+            })
+            .collect()
+    }
+
+    // A single read_vectored() into the part of segments that hasn't been read yet, returns the
+    // number of bytes read (zero at EOF)
+    pub fn read_from<R: Read, S: DerefMut<Target = [u8]>>(
+        &mut self,
+        reader: &mut R,
+        segments: &mut [S],
+    ) -> io::Result<usize> {
+        let n = reader.read_vectored(&mut self.io_slices_mut(segments))?;
+        self.advance(n);
+        Ok(n)
+    }
+
+    // A single write_vectored() from the part of segments that hasn't been written yet, returns
+    // the number of bytes written. Zero before is_complete() means that the writer doesn't accept
+    // any more data (cf. io::ErrorKind::WriteZero), loops until is_complete() have to stop on it.
+    pub fn write_to<W: Write, S: Deref<Target = [u8]>>(
+        &mut self,
+        writer: &mut W,
+        segments: &[S],
+    ) -> io::Result<usize> {
+        let n = writer.write_vectored(&self.io_slices(segments))?;
+        self.advance(n);
+        Ok(n)
+    }
+}
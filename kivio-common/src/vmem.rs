@@ -1,7 +1,26 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 
+#[allow(clippy::module_inception)]
 mod vmem;
-pub use vmem::{AnonMmap, VecU8, Vmem};
+pub use vmem::{Aligned, BoxU8, Foreign, StaticU8, VecU8, Vmem};
+
+mod anon_mmap;
+pub use anon_mmap::{AnonMmap, AnonMmapOptions};
+
+mod memfd;
+pub use memfd::Memfd;
+
+mod lazy;
+pub use lazy::{Lazy, LazyOptions, Loader};
+
+mod advice;
+pub use advice::Advice;
+
+mod resource;
+pub use resource::Reclaim;
+
+mod range_lock;
+pub use range_lock::{LockMode, RangeLockGuard, RangeLockGuardMut};
 
 mod handle;
 pub use handle::Handle;
@@ -9,6 +28,9 @@ pub use handle::Handle;
 mod handle_mut;
 pub use handle_mut::HandleMut;
 
+mod weak_handle;
+pub use weak_handle::WeakHandle;
+
 mod segment;
 pub use segment::Segment;
 
@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use crate::err::Error;
+
+use super::anon_mmap::helper::page_size;
+use super::Vmem;
+
+// See madvise(2)
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Advice {
+    WillNeed,
+    DontNeed,
+    Sequential,
+    Random,
+    Free,
+}
+
+impl Advice {
+    // Destructive advice may discard the contents of (private) pages
+    pub fn is_destructive(&self) -> bool {
+        matches!(self, Self::DontNeed | Self::Free)
+    }
+
+    fn to_libc(self) -> libc::c_int {
+        match self {
+            Self::WillNeed => libc::MADV_WILLNEED,
+            Self::DontNeed => libc::MADV_DONTNEED,
+            Self::Sequential => libc::MADV_SEQUENTIAL,
+            Self::Random => libc::MADV_RANDOM,
+            Self::Free => libc::MADV_FREE,
+        }
+    }
+}
+
+// Applies advice to the pages backing [ptr, ptr + len). Since madvise() works on whole pages,
+// destructive advice is only applied to the pages that are fully contained in the range (so
+// neighbouring segments are never affected), all other advice to all pages touched by the range.
+// Destructive advice requires exclusive access (i.e. a &mut SegmentMut, see SegmentMut::discard())
+// unless the memory is shared with a file, in which case the contents are preserved anyway.
+pub(super) fn advise(
+    vmem: &Vmem,
+    ptr: *const u8,
+    len: usize,
+    advice: Advice,
+    exclusive: bool,
+) -> Result<(), Error> {
+    let shared = match vmem {
+        Vmem::AnonMmap(_) => false,
+        Vmem::Memfd(_) => true,
+        _ => {
+            return Err(Error::UnsupportedOperation {
+                operation: format!("madvise({:?})", advice),
+                reason: format!(
+                    "{:?} is not backed by a memory mapping",
+                    vmem.backend_kind()
+                ),
+            })
+        }
+    };
+    if advice.is_destructive() && !exclusive && !shared {
+        return Err(Error::UnsupportedOperation {
+            operation: format!("madvise({:?})", advice),
+            reason: "Destructive advice for private memory requires SegmentMut::discard()"
+                .to_string(),
+        });
+    }
+    if advice == Advice::Free && shared {
+        return Err(Error::UnsupportedOperation {
+            operation: format!("madvise({:?})", advice),
+            reason: "Only private memory can be freed".to_string(),
+        });
+    }
+    let page_size = page_size();
+    let (start, end) = if advice.is_destructive() {
+        (
+            (ptr as usize).next_multiple_of(page_size),
+            (ptr as usize + len) / page_size * page_size,
+        )
+    } else {
+        (
+            (ptr as usize) / page_size * page_size,
+            (ptr as usize + len).next_multiple_of(page_size),
+        )
+    };
+    if start >= end {
+        return Ok(());
+    }
+    if unsafe { libc::madvise(start as *mut libc::c_void, end - start, advice.to_libc()) } != 0 {
+        return Err(Error::last_os_error("madvise"));
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::ptr::{self, NonNull};
+use std::sync::atomic::{self, AtomicBool, Ordering};
+
+use crate::err::Error;
+
+#[derive(Debug, Clone, Default)]
+pub struct AnonMmapOptions {
+    // Actually make the pages read-only (via mprotect) while the memory is accessed through a
+    // vmem::Handle and read-write again once it's converted back into a vmem::HandleMut. If
+    // mprotect() fails the infallible conversions (vmem::Handle::from_vmem() and from_handle_mut())
+    // leave the memory writable in release builds (see vmem::Handle::is_protected()) and panic in
+    // debug builds, use vmem::Handle::try_from_vmem() and try_from_handle_mut() to handle the error.
+    pub enforce_protection: bool,
+
+    // Debug mode: surround the memory with PROT_NONE guard pages and fill the slack between the
+    // end of the buffer and the trailing guard page with canary bytes that are checked once the
+    // last Handle or Segment is dropped. Meant to be used as guard_pages: cfg!(debug_assertions)
+    pub guard_pages: bool,
+
+    // For key material and the like: lock the pages into memory, exclude them from core dumps,
+    // don't let them be inherited by forked children and overwrite them with zeros on release
+    pub secure: bool,
+}
+
+const CANARY: u8 = 0xca;
+
+#[derive(Debug)]
+pub struct AnonMmap {
+    pub(super) mut_ptr: *mut u8,
+    pub(super) len: usize,
+    data_len: usize, // len rounded up to page size
+    map_ptr: *mut u8,
+    map_len: usize, // data_len plus guard pages (if any)
+    options: AnonMmapOptions,
+    protected: AtomicBool, // Currently mprotect()ed read-only
+}
+
+// The mapping is owned by the AnonMmap and only ever accessed through Handles and Segments, which
+// uphold the aliasing rules themselves.
+unsafe impl Send for AnonMmap {}
+unsafe impl Sync for AnonMmap {}
+
+impl AnonMmap {
+    pub fn new(len: usize) -> Result<Self, Error> {
+        Self::with_options(len, AnonMmapOptions::default())
+    }
+
+    pub fn with_options(len: usize, options: AnonMmapOptions) -> Result<Self, Error> {
+        let data_len = helper::round_up_to_page_size(len);
+        let guard_len = if options.guard_pages {
+            helper::page_size()
+        } else {
+            0
+        };
+        let map_len = data_len + 2 * guard_len;
+        let map_ptr = if map_len == 0 {
+            // mmap() refuses zero length mappings
+            NonNull::<u8>::dangling().as_ptr()
+        } else {
+            let p = unsafe {
+                libc::mmap(
+                    ptr::null_mut(),
+                    map_len,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                    -1,
+                    0,
+                )
+            };
+            if p == libc::MAP_FAILED {
+                return Err(Error::last_os_error("mmap"));
+            }
+            p as *mut u8
+        };
+        let m = Self {
+            mut_ptr: map_ptr.wrapping_add(guard_len),
+            len,
+            data_len,
+            map_ptr,
+            map_len,
+            options,
+            protected: AtomicBool::new(false),
+        };
+        if guard_len > 0 {
+            // m unmaps everything in case of errors
+            for guard_ptr in [map_ptr, map_ptr.wrapping_add(guard_len + data_len)] {
+                if unsafe { libc::mprotect(guard_ptr as *mut libc::c_void, guard_len, 0) } != 0 {
+                    return Err(Error::last_os_error("mprotect"));
+                }
+            }
+            unsafe { ptr::write_bytes(m.mut_ptr.add(len), CANARY, data_len - len) };
+        }
+        if m.options.secure && data_len > 0 {
+            let p = m.mut_ptr as *mut libc::c_void;
+            if unsafe { libc::mlock(p, data_len) } != 0 {
+                return Err(Error::last_os_error("mlock"));
+            }
+            for advice in [libc::MADV_DONTDUMP, libc::MADV_WIPEONFORK] {
+                if unsafe { libc::madvise(p, data_len, advice) } != 0 {
+                    return Err(Error::last_os_error("madvise"));
+                }
+            }
+        }
+        Ok(m)
+    }
+
+    pub fn options(&self) -> &AnonMmapOptions {
+        &self.options
+    }
+
+    pub(super) fn data_len(&self) -> usize {
+        self.data_len
+    }
+
+    pub fn is_protected(&self) -> bool {
+        self.protected.load(Ordering::Acquire)
+    }
+
+    // No-op unless guard_pages is set
+    pub fn check_canaries(&self) -> Result<(), Error> {
+        if !self.options.guard_pages {
+            return Ok(());
+        }
+        let slack = unsafe {
+            std::slice::from_raw_parts(self.mut_ptr.add(self.len), self.data_len - self.len)
+        };
+        match slack.iter().position(|&b| b != CANARY) {
+            None => Ok(()),
+            Some(pos) => Err(Error::CorruptedCanary {
+                offset: self.len + pos,
+                len: self.len,
+            }),
+        }
+    }
+
+    // No-op unless secure is set. Called from drop(), so it must not fail: if the pages can't be
+    // made writable again they are discarded instead, which zero-fills private anonymous pages
+    // whatever their protection (but not while they are locked).
+    pub(super) fn wipe(&self) {
+        if !self.options.secure || self.data_len == 0 {
+            return;
+        }
+        if self.protect(false).is_ok() {
+            // Volatile writes can't be elided even though the memory is never read again
+            for i in 0..self.len {
+                unsafe { self.mut_ptr.add(i).write_volatile(0) };
+            }
+            atomic::compiler_fence(Ordering::SeqCst);
+            return;
+        }
+        // Page by page, so that a page that is gone (which is what makes mprotect() fail) doesn't
+        // stop the others from being discarded. They are locked again in case the memory is
+        // recycled.
+        let page_size = helper::page_size();
+        for offset in (0..self.data_len).step_by(page_size) {
+            let p = self.mut_ptr.wrapping_add(offset) as *mut libc::c_void;
+            unsafe {
+                libc::munlock(p, page_size);
+                libc::madvise(p, page_size, libc::MADV_DONTNEED);
+                libc::mlock(p, page_size);
+            }
+        }
+    }
+
+    // No-op unless enforce_protection is set
+    pub(super) fn protect(&self, read_only: bool) -> Result<(), Error> {
+        if !self.options.enforce_protection || self.data_len == 0 {
+            return Ok(());
+        }
+        let prot = if read_only {
+            libc::PROT_READ
+        } else {
+            libc::PROT_READ | libc::PROT_WRITE
+        };
+        if unsafe { libc::mprotect(self.mut_ptr as *mut libc::c_void, self.data_len, prot) } != 0 {
+            return Err(Error::last_os_error("mprotect"));
+        }
+        self.protected.store(read_only, Ordering::Release);
+        Ok(())
+    }
+}
+
+impl Drop for AnonMmap {
+    fn drop(&mut self) {
+        if self.map_len > 0 {
+            self.wipe();
+            unsafe { libc::munmap(self.map_ptr as *mut libc::c_void, self.map_len) };
+        }
+    }
+}
+
+pub(crate) mod helper {
+
+    pub(crate) fn page_size() -> usize {
+        unsafe { libc::sysconf(libc::_SC_PAGESIZE) as usize }
+    }
+
+    pub(crate) fn round_up_to_page_size(len: usize) -> usize {
+        let page_size = page_size();
+        len.div_ceil(page_size) * page_size
+    }
+}
@@ -1,22 +1,87 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 
 use std::convert::From;
+use std::fmt;
 use std::sync::Arc;
 
+use crate::err::Error;
 use crate::traits;
 
-use super::{HandleMut, Segment, Vmem};
+use super::resource::Resource;
+use super::{HandleMut, Segment, Vmem, WeakHandle};
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Handle {
-    pub(super) vmem: Arc<Vmem>,
+    pub(super) vmem: Arc<Resource>,
+}
+
+impl Handle {
+    // If the Vmem asks for its protection to be enforced but that fails (e.g. mprotect() running
+    // into the limit of memory mappings) the memory stays writable, which is reported by
+    // is_protected(). Debug builds panic instead. Use try_from_vmem() to get an error.
+    pub fn from_vmem(vmem: Vmem) -> Self {
+        protect_or_fall_back(&vmem);
+        Self {
+            vmem: Arc::new(Resource::new(vmem, None)),
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub fn try_from_vmem(vmem: Vmem) -> Result<Self, (Error, Vmem)> {
+        match vmem.protect(true) {
+            Ok(()) => Ok(Self {
+                vmem: Arc::new(Resource::new(vmem, None)),
+            }),
+            Err(e) => Err((e, vmem)),
+        }
+    }
+
+    // Like traits::Handle::from_handle_mut() but fails instead of leaving the memory writable if
+    // enforcing its protection fails
+    pub fn try_from_handle_mut(handle_mut: HandleMut) -> Result<Self, (Error, HandleMut)> {
+        match handle_mut.vmem.protect(true) {
+            Ok(()) => Ok(Self {
+                vmem: handle_mut.vmem,
+            }),
+            Err(e) => Err((e, handle_mut)),
+        }
+    }
+
+    // True if writes to the memory actually fault (see AnonMmapOptions::enforce_protection)
+    pub fn is_protected(&self) -> bool {
+        self.vmem.is_protected()
+    }
+
+    pub(crate) fn vmem(&self) -> &Vmem {
+        &self.vmem
+    }
+
+    pub fn downgrade(&self) -> WeakHandle {
+        WeakHandle {
+            vmem: Arc::<Resource>::downgrade(&self.vmem),
+        }
+    }
+}
+
+// Leaves the memory writable if its protection can't be enforced, but fails loudly in debug builds
+// where that is most likely a bug
+fn protect_or_fall_back(vmem: &Vmem) {
+    if let Err(e) = vmem.protect(true) {
+        debug_assert!(
+            false,
+            "Failed to enforce the protection of the memory: {}",
+            e
+        );
+    }
 }
 
 impl traits::Handle for Handle {
     type HandleMut = HandleMut;
     type Segment = Segment;
 
+    // See from_vmem() for what happens if enforcing the protection fails
     fn from_handle_mut(handle_mut: Self::HandleMut) -> Self {
+        protect_or_fall_back(&handle_mut.vmem);
         Self {
             vmem: handle_mut.vmem,
         }
@@ -25,6 +90,32 @@ impl traits::Handle for Handle {
     fn from_segment(segment: Self::Segment) -> Self {
         Self { vmem: segment.vmem }
     }
+
+    fn len(&self) -> usize {
+        self.vmem.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.vmem.capacity()
+    }
+
+    fn backend_kind(&self) -> traits::BackendKind {
+        self.vmem.backend_kind()
+    }
+
+    fn outstanding_refs(&self) -> usize {
+        Arc::<Resource>::strong_count(&self.vmem) - 1
+    }
+}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Handle")
+            .field("backend_kind", &self.vmem.backend_kind())
+            .field("len", &self.vmem.len())
+            .field("outstanding_refs", &traits::Handle::outstanding_refs(self))
+            .finish()
+    }
 }
 
 impl From<HandleMut> for Handle {
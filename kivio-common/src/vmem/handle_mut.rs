@@ -2,24 +2,64 @@
 
 use std::any::type_name;
 use std::convert::TryFrom;
+use std::fmt;
 use std::sync::Arc;
 
 use crate::err::Error;
 use crate::traits;
 
+use super::resource::{Reclaim, Resource};
 use super::{Handle, SegmentMut, Vmem};
 
-#[derive(Debug)]
 pub struct HandleMut {
-    pub(super) vmem: Arc<Vmem>,
+    pub(super) vmem: Arc<Resource>,
 }
 
 impl HandleMut {
+    // Panics for read-only Vmems and if the memory can't be made writable again, see
+    // try_from_vmem()
     pub fn from_vmem(vmem: Vmem) -> Self {
-        Self {
-            vmem: Arc::new(vmem),
+        Self::try_from_vmem(vmem).unwrap_or_else(|(e, _)| panic!("{}", e))
+    }
+
+    // Panics like from_vmem(), see try_from_vmem_with_reclaim()
+    pub fn from_vmem_with_reclaim(vmem: Vmem, reclaim: Reclaim) -> Self {
+        Self::try_from_vmem_with_reclaim(vmem, reclaim).unwrap_or_else(|(e, _)| panic!("{}", e))
+    }
+
+    // Fails for read-only Vmems (use vmem::Handle::from_vmem() for those) and if the memory can't
+    // be made writable again (e.g. when it comes from a reclaimed vmem::Handle)
+    #[allow(clippy::result_large_err)]
+    pub fn try_from_vmem(vmem: Vmem) -> Result<Self, (Error, Vmem)> {
+        match helper::check_writable(&vmem) {
+            Ok(()) => Ok(Self {
+                vmem: Arc::new(Resource::new(vmem, None)),
+            }),
+            Err(e) => Err((e, vmem)),
+        }
+    }
+
+    // The reclaim action is triggered once the last Handle or Segment derived from this HandleMut
+    // is dropped. It is not triggered by conversions between Handles and HandleMuts. The reclaim
+    // action is dropped (without being triggered) if the conversion fails.
+    #[allow(clippy::result_large_err)]
+    pub fn try_from_vmem_with_reclaim(vmem: Vmem, reclaim: Reclaim) -> Result<Self, (Error, Vmem)> {
+        match helper::check_writable(&vmem) {
+            Ok(()) => Ok(Self {
+                vmem: Arc::new(Resource::new(vmem, Some(reclaim))),
+            }),
+            Err(e) => Err((e, vmem)),
         }
     }
+
+    pub(crate) fn vmem(&self) -> &Vmem {
+        &self.vmem
+    }
+
+    pub(crate) fn vmem_mut(&mut self) -> &mut Vmem {
+        Arc::<Resource>::get_mut(&mut self.vmem)
+            .expect("Spurious reference to vmem::HandleMut's Vmem")
+    }
 }
 
 impl traits::HandleMut for HandleMut {
@@ -27,8 +67,21 @@ impl traits::HandleMut for HandleMut {
     type SegmentMut = SegmentMut;
 
     fn try_from_handle(handle: Self::Handle) -> Result<Self, (Error, Self::Handle)> {
-        match Arc::<Vmem>::try_unwrap(handle.vmem) {
-            Ok(i) => Ok(Self { vmem: Arc::new(i) }),
+        if handle.vmem.is_read_only() {
+            return Err((
+                Error::ConversionFailed {
+                    from_type: type_name::<Self::Handle>().to_string(),
+                    to_type: type_name::<Self>().to_string(),
+                    reason: "vmem::Handle is backed by read-only memory".to_string(),
+                },
+                handle,
+            ));
+        }
+        match Arc::<Resource>::try_unwrap(handle.vmem) {
+            Ok(i) => match i.protect(false) {
+                Ok(()) => Ok(Self { vmem: Arc::new(i) }),
+                Err(e) => Err((e, Self::Handle { vmem: Arc::new(i) })),
+            },
             Err(arc_i) => Err((
                 Error::ConversionFailed {
                     from_type: type_name::<Self::Handle>().to_string(),
@@ -45,7 +98,7 @@ impl traits::HandleMut for HandleMut {
     fn try_from_segment_mut(
         segment_mut: Self::SegmentMut,
     ) -> Result<Self, (Error, Self::SegmentMut)> {
-        match Arc::<Vmem>::try_unwrap(segment_mut.vmem) {
+        match Arc::<Resource>::try_unwrap(segment_mut.vmem) {
             Ok(v) => Ok(Self { vmem: Arc::new(v) }),
             Err(arc_v) => Err((
                 Error::ConversionFailed {
@@ -63,6 +116,43 @@ impl traits::HandleMut for HandleMut {
             )),
         }
     }
+
+    fn len(&self) -> usize {
+        self.vmem.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.vmem.capacity()
+    }
+
+    fn backend_kind(&self) -> traits::BackendKind {
+        self.vmem.backend_kind()
+    }
+
+    fn outstanding_refs(&self) -> usize {
+        Arc::<Resource>::strong_count(&self.vmem) - 1
+    }
+}
+
+impl fmt::Debug for HandleMut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandleMut")
+            .field("backend_kind", &self.vmem.backend_kind())
+            .field("len", &self.vmem.len())
+            .field(
+                "outstanding_refs",
+                &traits::HandleMut::outstanding_refs(self),
+            )
+            .finish()
+    }
+}
+
+impl TryFrom<Vmem> for HandleMut {
+    type Error = (Error, Vmem);
+
+    fn try_from(item: Vmem) -> Result<Self, Self::Error> {
+        Self::try_from_vmem(item)
+    }
 }
 
 impl TryFrom<Handle> for HandleMut {
@@ -80,3 +170,23 @@ impl TryFrom<SegmentMut> for HandleMut {
         traits::HandleMut::try_from_segment_mut(item)
     }
 }
+
+mod helper {
+
+    use std::any::type_name;
+
+    use crate::err::Error;
+
+    use super::{HandleMut, Vmem};
+
+    pub(super) fn check_writable(vmem: &Vmem) -> Result<(), Error> {
+        if vmem.is_read_only() {
+            return Err(Error::ConversionFailed {
+                from_type: type_name::<Vmem>().to_string(),
+                to_type: type_name::<HandleMut>().to_string(),
+                reason: "Vmem is read-only, use vmem::Handle::from_vmem()".to_string(),
+            });
+        }
+        vmem.protect(false)
+    }
+}
@@ -0,0 +1,319 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::fmt;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+use std::process;
+use std::ptr::{self, NonNull};
+use std::thread::{self, JoinHandle};
+
+use crate::err::Error;
+
+use super::anon_mmap::helper::{page_size, round_up_to_page_size};
+
+// Called with the offset of a page and a buffer of (at most) page size to fill with the contents
+// of the memory at that offset. There is no way to report errors: the thread accessing the page
+// can't continue until the page is filled, so if the loader panics the process is aborted.
+pub type Loader = Box<dyn FnMut(usize, &mut [u8]) + Send>;
+
+#[derive(Debug, Clone, Default)]
+pub struct LazyOptions {
+    // Only handle page faults triggered from user space (UFFD_USER_MODE_ONLY), which unprivileged
+    // processes may be restricted to (see the vm.unprivileged_userfaultfd sysctl). Kernel accesses
+    // to pages that haven't been loaded yet then fail with EFAULT instead of loading them, which
+    // includes system calls like read(), readv() or sendmsg() on the memory.
+    pub user_mode_only: bool,
+}
+
+// Memory whose pages are populated by a Loader on first access (see userfaultfd(2))
+pub struct Lazy {
+    pub(super) mut_ptr: *mut u8,
+    pub(super) len: usize,
+    map_len: usize,
+    stop_fd: Option<OwnedFd>,
+    handler: Option<JoinHandle<()>>,
+}
+
+// See Aligned
+unsafe impl Send for Lazy {}
+unsafe impl Sync for Lazy {}
+
+impl Lazy {
+    pub fn new(len: usize, loader: Loader) -> Result<Self, Error> {
+        Self::with_options(len, loader, LazyOptions::default())
+    }
+
+    pub fn with_options(len: usize, loader: Loader, options: LazyOptions) -> Result<Self, Error> {
+        let map_len = round_up_to_page_size(len);
+        if map_len == 0 {
+            return Ok(Self {
+                mut_ptr: NonNull::<u8>::dangling().as_ptr(),
+                len,
+                map_len,
+                stop_fd: None,
+                handler: None,
+            });
+        }
+        let p = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                map_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if p == libc::MAP_FAILED {
+            return Err(Error::last_os_error("mmap"));
+        }
+        // From here on drop() takes care of cleaning up
+        let mut lazy = Self {
+            mut_ptr: p as *mut u8,
+            len,
+            map_len,
+            stop_fd: None,
+            handler: None,
+        };
+        let uffd = helper::register(lazy.mut_ptr, map_len, options.user_mode_only)?;
+        let stop_fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC) };
+        if stop_fd < 0 {
+            return Err(Error::last_os_error("eventfd"));
+        }
+        let stop_fd = unsafe { OwnedFd::from_raw_fd(stop_fd) };
+        let raw_stop_fd = stop_fd.as_raw_fd();
+        lazy.stop_fd = Some(stop_fd);
+        let base = lazy.mut_ptr as usize;
+        lazy.handler = Some(
+            thread::Builder::new()
+                .name("kivio-lazy".to_string())
+                .spawn(move || helper::handle_faults(uffd, raw_stop_fd, base, len, loader))
+                .map_err(|e| Error::SystemCallFailed {
+                    call: "clone".to_string(),
+                    source: e,
+                })?,
+        );
+        Ok(lazy)
+    }
+
+    pub(super) fn map_len(&self) -> usize {
+        self.map_len
+    }
+}
+
+impl Drop for Lazy {
+    fn drop(&mut self) {
+        if let Some(stop_fd) = self.stop_fd.take() {
+            let one: u64 = 1;
+            unsafe {
+                libc::write(
+                    stop_fd.as_raw_fd(),
+                    &one as *const u64 as *const libc::c_void,
+                    8,
+                )
+            };
+            if let Some(handler) = self.handler.take() {
+                let _ = handler.join();
+            }
+        }
+        if self.map_len > 0 {
+            unsafe { libc::munmap(self.mut_ptr as *mut libc::c_void, self.map_len) };
+        }
+    }
+}
+
+impl fmt::Debug for Lazy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy")
+            .field("len", &self.len)
+            .field("map_len", &self.map_len)
+            .finish()
+    }
+}
+
+mod helper {
+
+    use std::mem;
+    use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::panic::{self, AssertUnwindSafe};
+
+    use crate::err::Error;
+
+    use super::{page_size, process, Loader};
+
+    // The userfaultfd ABI is not part of the libc crate, see linux/userfaultfd.h
+
+    const UFFD_API: u64 = 0xaa;
+    const UFFD_USER_MODE_ONLY: libc::c_int = 1;
+    const UFFD_EVENT_PAGEFAULT: u8 = 0x12;
+    const UFFDIO_REGISTER_MODE_MISSING: u64 = 1;
+
+    #[repr(C)]
+    struct UffdioApi {
+        api: u64,
+        features: u64,
+        ioctls: u64,
+    }
+
+    #[repr(C)]
+    struct UffdioRange {
+        start: u64,
+        len: u64,
+    }
+
+    #[repr(C)]
+    struct UffdioRegister {
+        range: UffdioRange,
+        mode: u64,
+        ioctls: u64,
+    }
+
+    #[repr(C)]
+    struct UffdioCopy {
+        dst: u64,
+        src: u64,
+        len: u64,
+        mode: u64,
+        copy: i64,
+    }
+
+    // Only the page fault variant of the union is of interest
+    #[repr(C)]
+    struct UffdMsg {
+        event: u8,
+        reserved1: u8,
+        reserved2: u16,
+        reserved3: u32,
+        pagefault_flags: u64,
+        pagefault_address: u64,
+        pagefault_feat: u64,
+    }
+
+    const fn iowr<T>(nr: u64) -> libc::c_ulong {
+        ((3 << 30) | ((mem::size_of::<T>() as u64) << 16) | (0xaa << 8) | nr) as libc::c_ulong
+    }
+
+    const UFFDIO_API: libc::c_ulong = iowr::<UffdioApi>(0x3f);
+    const UFFDIO_REGISTER: libc::c_ulong = iowr::<UffdioRegister>(0x00);
+    const UFFDIO_COPY: libc::c_ulong = iowr::<UffdioCopy>(0x03);
+
+    pub(super) fn register(
+        mut_ptr: *mut u8,
+        map_len: usize,
+        user_mode_only: bool,
+    ) -> Result<OwnedFd, Error> {
+        let mut flags = libc::O_CLOEXEC | libc::O_NONBLOCK;
+        if user_mode_only {
+            flags |= UFFD_USER_MODE_ONLY;
+        }
+        let raw_fd = unsafe { libc::syscall(libc::SYS_userfaultfd, flags) } as RawFd;
+        if raw_fd < 0 {
+            return Err(Error::last_os_error("userfaultfd"));
+        }
+        let uffd = unsafe { OwnedFd::from_raw_fd(raw_fd) };
+        let mut api = UffdioApi {
+            api: UFFD_API,
+            features: 0,
+            ioctls: 0,
+        };
+        if unsafe { libc::ioctl(raw_fd, UFFDIO_API, &mut api) } != 0 {
+            return Err(Error::last_os_error("ioctl(UFFDIO_API)"));
+        }
+        let mut register = UffdioRegister {
+            range: UffdioRange {
+                start: mut_ptr as u64,
+                len: map_len as u64,
+            },
+            mode: UFFDIO_REGISTER_MODE_MISSING,
+            ioctls: 0,
+        };
+        if unsafe { libc::ioctl(raw_fd, UFFDIO_REGISTER, &mut register) } != 0 {
+            return Err(Error::last_os_error("ioctl(UFFDIO_REGISTER)"));
+        }
+        Ok(uffd)
+    }
+
+    pub(super) fn handle_faults(
+        uffd: OwnedFd,
+        stop_fd: RawFd,
+        base: usize,
+        len: usize,
+        mut loader: Loader,
+    ) {
+        let page_size = page_size();
+        let mut buf = vec![0u8; page_size];
+        let mut fds = [
+            libc::pollfd {
+                fd: uffd.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            },
+            libc::pollfd {
+                fd: stop_fd,
+                events: libc::POLLIN,
+                revents: 0,
+            },
+        ];
+        loop {
+            if unsafe { libc::poll(fds.as_mut_ptr(), 2, -1) } < 0 {
+                if errno() == libc::EINTR {
+                    continue;
+                }
+                // Faulting threads would never be woken up
+                process::abort();
+            }
+            if fds[1].revents != 0 {
+                return;
+            }
+            let mut msg = mem::MaybeUninit::<UffdMsg>::uninit();
+            let n = unsafe {
+                libc::read(
+                    uffd.as_raw_fd(),
+                    msg.as_mut_ptr() as *mut libc::c_void,
+                    mem::size_of::<UffdMsg>(),
+                )
+            };
+            if n != mem::size_of::<UffdMsg>() as isize {
+                continue; // EAGAIN (someone else resolved the fault)
+            }
+            let msg = unsafe { msg.assume_init() };
+            if msg.event != UFFD_EVENT_PAGEFAULT {
+                continue;
+            }
+            let page = (msg.pagefault_address as usize) & !(page_size - 1);
+            let offset = page - base;
+            buf.fill(0);
+            let n = len.saturating_sub(offset).min(page_size);
+            if panic::catch_unwind(AssertUnwindSafe(|| loader(offset, &mut buf[..n]))).is_err() {
+                // The faulting thread would be stuck forever
+                process::abort();
+            }
+            let mut copy = UffdioCopy {
+                dst: page as u64,
+                src: buf.as_ptr() as u64,
+                len: page_size as u64,
+                mode: 0,
+                copy: 0,
+            };
+            loop {
+                if unsafe { libc::ioctl(uffd.as_raw_fd(), UFFDIO_COPY, &mut copy) } == 0 {
+                    break;
+                }
+                match errno() {
+                    // The page was populated in the meantime
+                    libc::EEXIST => break,
+                    // The memory layout is changing, the copy has to be retried
+                    libc::EAGAIN | libc::EINTR => {
+                        copy.copy = 0;
+                        continue;
+                    }
+                    // The faulting thread would be stuck forever
+                    _ => process::abort(),
+                }
+            }
+        }
+    }
+
+    fn errno() -> i32 {
+        std::io::Error::last_os_error().raw_os_error().unwrap_or(0)
+    }
+}
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::ffi::CString;
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::ptr::NonNull;
+
+use crate::err::Error;
+
+use super::anon_mmap::helper::round_up_to_page_size;
+
+const SEALS: libc::c_int =
+    libc::F_SEAL_SEAL | libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE;
+
+// Memory that is both a shared mapping and a file descriptor (see memfd_create(2)), so it can be
+// passed to other processes
+#[derive(Debug)]
+pub struct Memfd {
+    fd: OwnedFd,
+    pub(super) mut_ptr: *mut u8,
+    pub(super) len: usize,
+    map_len: usize,
+    pub(super) read_only: bool,
+}
+
+// See Aligned
+unsafe impl Send for Memfd {}
+unsafe impl Sync for Memfd {}
+
+impl Memfd {
+    pub fn new(name: &str, len: usize) -> Result<Self, Error> {
+        let c_name = CString::new(name).map_err(|e| Error::SystemCallFailed {
+            call: "memfd_create".to_string(),
+            source: io::Error::new(io::ErrorKind::InvalidInput, e),
+        })?;
+        let raw_fd = unsafe {
+            libc::memfd_create(c_name.as_ptr(), libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING)
+        };
+        if raw_fd < 0 {
+            return Err(Error::last_os_error("memfd_create"));
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(raw_fd) };
+        if unsafe { libc::ftruncate(raw_fd, len as libc::off_t) } != 0 {
+            return Err(Error::last_os_error("ftruncate"));
+        }
+        Self::map(fd, len, false)
+    }
+
+    // Maps a memfd (e.g. received from another process) read-only. The memfd is opened again with
+    // the same access mode (and fd closed), so that it gets an open file description of its own and
+    // locks taken through it contend with those of the process it was received from (see fcntl(2)).
+    pub fn from_fd(fd: OwnedFd) -> Result<Self, Error> {
+        let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+        if flags < 0 {
+            return Err(Error::last_os_error("fcntl"));
+        }
+        let fd = helper::reopen(fd.as_raw_fd(), flags & libc::O_ACCMODE)?;
+        let mut stat = unsafe { std::mem::zeroed::<libc::stat>() };
+        if unsafe { libc::fstat(fd.as_raw_fd(), &mut stat) } != 0 {
+            return Err(Error::last_os_error("fstat"));
+        }
+        Self::map(fd, stat.st_size as usize, true)
+    }
+
+    fn map(fd: OwnedFd, len: usize, read_only: bool) -> Result<Self, Error> {
+        let map_len = round_up_to_page_size(len);
+        let mut_ptr = if map_len == 0 {
+            NonNull::<u8>::dangling().as_ptr()
+        } else {
+            helper::mmap_shared(fd.as_raw_fd(), map_len, read_only)?
+        };
+        Ok(Self {
+            fd,
+            mut_ptr,
+            len,
+            map_len,
+            read_only,
+        })
+    }
+
+    pub fn fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+
+    pub(super) fn map_len(&self) -> usize {
+        self.map_len
+    }
+
+    // True if the contents can't be changed anymore by anyone holding the file descriptor
+    pub fn is_sealed(&self) -> bool {
+        let seals = unsafe { libc::fcntl(self.fd.as_raw_fd(), libc::F_GET_SEALS) };
+        seals >= 0 && seals & libc::F_SEAL_WRITE != 0
+    }
+
+    // Remaps the memory read-only and seals it against any further modifications. If sealing
+    // fails the memory is mapped writable again, and in the unlikely case that this fails as well
+    // it stays mapped read-only (see is_read_only()), which callers have to check since the memory
+    // can't be handed out through a HandleMut anymore.
+    pub(crate) fn seal(&mut self) -> Result<(), Error> {
+        if self.map_len == 0 {
+            if unsafe { libc::fcntl(self.fd.as_raw_fd(), libc::F_ADD_SEALS, SEALS) } != 0 {
+                return Err(Error::last_os_error("fcntl"));
+            }
+            self.read_only = true;
+            return Ok(());
+        }
+        // Sealing against writes fails as long as there is any shared mapping that is or could be
+        // made writable, which includes our own. A mapping through a read-only file description
+        // doesn't count, so one is set up before giving up the writable one.
+        let ro_fd = helper::reopen(self.fd.as_raw_fd(), libc::O_RDONLY)?;
+        let ro_ptr = helper::mmap_shared(ro_fd.as_raw_fd(), self.map_len, true)?;
+        unsafe { libc::munmap(self.mut_ptr as *mut libc::c_void, self.map_len) };
+        self.mut_ptr = ro_ptr;
+        self.read_only = true;
+        if unsafe { libc::fcntl(self.fd.as_raw_fd(), libc::F_ADD_SEALS, SEALS) } == 0 {
+            return Ok(());
+        }
+        let e = Error::last_os_error("fcntl");
+        if let Ok(mut_ptr) = helper::mmap_shared(self.fd.as_raw_fd(), self.map_len, false) {
+            unsafe { libc::munmap(ro_ptr as *mut libc::c_void, self.map_len) };
+            self.mut_ptr = mut_ptr;
+            self.read_only = false;
+        }
+        Err(e)
+    }
+}
+
+impl Drop for Memfd {
+    fn drop(&mut self) {
+        if self.map_len > 0 {
+            unsafe { libc::munmap(self.mut_ptr as *mut libc::c_void, self.map_len) };
+        }
+    }
+}
+
+pub(crate) mod helper {
+
+    use std::ffi::CString;
+    use std::os::unix::io::{FromRawFd, OwnedFd, RawFd};
+    use std::ptr;
+
+    use crate::err::Error;
+
+    // Opens fd again, which (unlike dup()) results in a new open file description
+    pub(crate) fn reopen(fd: RawFd, flags: libc::c_int) -> Result<OwnedFd, Error> {
+        let path = CString::new(format!("/proc/self/fd/{}", fd)).unwrap();
+        let raw_fd = unsafe { libc::open(path.as_ptr(), flags | libc::O_CLOEXEC) };
+        if raw_fd < 0 {
+            return Err(Error::last_os_error("open"));
+        }
+        Ok(unsafe { OwnedFd::from_raw_fd(raw_fd) })
+    }
+
+    pub(super) fn mmap_shared(
+        fd: RawFd,
+        map_len: usize,
+        read_only: bool,
+    ) -> Result<*mut u8, Error> {
+        let prot = if read_only {
+            libc::PROT_READ
+        } else {
+            libc::PROT_READ | libc::PROT_WRITE
+        };
+        let p = unsafe { libc::mmap(ptr::null_mut(), map_len, prot, libc::MAP_SHARED, fd, 0) };
+        if p == libc::MAP_FAILED {
+            return Err(Error::last_os_error("mmap"));
+        }
+        Ok(p as *mut u8)
+    }
+}
@@ -0,0 +1,294 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::os::unix::io::RawFd;
+use std::slice;
+use std::sync::{Condvar, Mutex};
+
+use crate::err::Error;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LockMode {
+    Shared,
+    Exclusive,
+}
+
+#[derive(Debug)]
+struct Entry {
+    id: u64,
+    start: usize,
+    end: usize,
+    mode: LockMode,
+    // The open file description the range is (being) locked on as well
+    ofd: Option<RawFd>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    next_id: u64,
+    entries: Vec<Entry>,
+}
+
+// The advisory byte range locks held on a Vmem within this process, offsets are relative to the
+// start of the Vmem so that the Segments returned by try_split() contend with each other
+#[derive(Debug, Default)]
+pub(super) struct LockTable {
+    state: Mutex<Entries>,
+    released: Condvar,
+}
+
+impl LockTable {
+    // Returns the id of the new lock, Err(RangeLocked) if !blocking and the range is contended.
+    // Note that locks are not reentrant: waiting for a conflicting lock held by the same thread
+    // blocks forever.
+    fn acquire(
+        &self,
+        start: usize,
+        end: usize,
+        mode: LockMode,
+        blocking: bool,
+        ofd: Option<RawFd>,
+    ) -> Result<u64, Error> {
+        let conflicts = |e: &Entry| {
+            e.start < end
+                && start < e.end
+                && (e.mode == LockMode::Exclusive || mode == LockMode::Exclusive)
+        };
+        let mut state = self.state.lock().unwrap();
+        while state.entries.iter().any(conflicts) {
+            if !blocking {
+                return Err(Error::RangeLocked { start, end });
+            }
+            state = self.released.wait(state).unwrap();
+        }
+        let id = state.next_id;
+        state.next_id += 1;
+        state.entries.push(Entry {
+            id,
+            start,
+            end,
+            mode,
+            ofd,
+        });
+        Ok(id)
+    }
+
+    fn release(&self, id: u64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(i) = state.entries.iter().position(|e| e.id == id) {
+            let entry = state.entries.remove(i);
+            if let Some(fd) = entry.ofd {
+                // fcntl() merges the locks taken through the same open file description, so only
+                // the parts of the range that no other lock covers can be unlocked. This happens
+                // under the mutex so that a lock being taken concurrently isn't unlocked again.
+                let covered = state
+                    .entries
+                    .iter()
+                    .filter(|e| e.ofd == Some(fd))
+                    .map(|e| (e.start, e.end))
+                    .collect();
+                for (start, end) in helper::uncovered(entry.start, entry.end, covered) {
+                    let _ = helper::fcntl_lock(
+                        fd,
+                        libc::F_OFD_SETLK,
+                        libc::F_UNLCK,
+                        start,
+                        end - start,
+                    );
+                }
+            }
+        }
+        drop(state);
+        self.released.notify_all();
+    }
+
+    // If ofd is given the range is additionally locked on that open file description (see
+    // fcntl(2)), at the same offsets, so that the lock also excludes other processes. Note that
+    // processes only contend with each other if they use different open file descriptions (see
+    // memfd::Handle::recv()).
+    pub(super) fn lock(
+        &self,
+        start: usize,
+        len: usize,
+        mode: LockMode,
+        blocking: bool,
+        ofd: Option<RawFd>,
+    ) -> Result<Held<'_>, Error> {
+        let held = Held {
+            table: self,
+            id: self.acquire(start, start + len, mode, blocking, ofd)?,
+        };
+        // An l_len of zero would lock up to the end of the file
+        if let (Some(fd), true) = (ofd, len > 0) {
+            // On failure dropping held releases the table entry again
+            helper::ofd_lock(fd, start, len, mode, blocking)?;
+        }
+        Ok(held)
+    }
+}
+
+// A lock in a LockTable, released when dropped
+pub(super) struct Held<'a> {
+    table: &'a LockTable,
+    id: u64,
+}
+
+impl Drop for Held<'_> {
+    fn drop(&mut self) {
+        self.table.release(self.id);
+    }
+}
+
+// Shared access to the locked range, releases the lock when dropped. Borrows the Segment or
+// SegmentMut the lock was taken through, which therefore can't be converted or merged while the
+// lock is held.
+pub struct RangeLockGuard<'a> {
+    pub(super) held: Held<'a>,
+    pub(super) ptr: *const u8,
+    pub(super) len: usize,
+}
+
+// Exclusive access to the locked range, see RangeLockGuard
+pub struct RangeLockGuardMut<'a> {
+    pub(super) held: Held<'a>,
+    pub(super) mut_ptr: *mut u8,
+    pub(super) len: usize,
+    pub(super) _marker: PhantomData<&'a mut [u8]>,
+}
+
+// Like &[u8] and &mut [u8]
+unsafe impl Send for RangeLockGuard<'_> {}
+unsafe impl Sync for RangeLockGuard<'_> {}
+unsafe impl Send for RangeLockGuardMut<'_> {}
+unsafe impl Sync for RangeLockGuardMut<'_> {}
+
+impl Deref for RangeLockGuard<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl Deref for RangeLockGuardMut<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.mut_ptr, self.len) }
+    }
+}
+
+impl DerefMut for RangeLockGuardMut<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.mut_ptr, self.len) }
+    }
+}
+
+impl fmt::Debug for RangeLockGuard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RangeLockGuard")
+            .field("id", &self.held.id)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl fmt::Debug for RangeLockGuardMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RangeLockGuardMut")
+            .field("id", &self.held.id)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+mod helper {
+
+    use std::io;
+    use std::os::unix::io::RawFd;
+
+    use crate::err::Error;
+
+    use super::LockMode;
+
+    pub(super) fn ofd_lock(
+        fd: RawFd,
+        offset: usize,
+        len: usize,
+        mode: LockMode,
+        blocking: bool,
+    ) -> Result<(), Error> {
+        let lock_type = match mode {
+            LockMode::Shared => libc::F_RDLCK,
+            LockMode::Exclusive => libc::F_WRLCK,
+        };
+        let cmd = if blocking {
+            libc::F_OFD_SETLKW
+        } else {
+            libc::F_OFD_SETLK
+        };
+        loop {
+            match fcntl_lock(fd, cmd, lock_type, offset, len) {
+                Ok(()) => return Ok(()),
+                Err(e) if e.raw_os_error() == Some(libc::EINTR) => continue,
+                Err(e) if matches!(e.raw_os_error(), Some(libc::EAGAIN) | Some(libc::EACCES)) => {
+                    return Err(Error::RangeLocked {
+                        start: offset,
+                        end: offset + len,
+                    })
+                }
+                Err(e) => {
+                    return Err(Error::SystemCallFailed {
+                        call: "fcntl".to_string(),
+                        source: e,
+                    })
+                }
+            }
+        }
+    }
+
+    pub(super) fn fcntl_lock(
+        fd: RawFd,
+        cmd: libc::c_int,
+        lock_type: libc::c_int,
+        offset: usize,
+        len: usize,
+    ) -> Result<(), io::Error> {
+        let mut flock = unsafe { std::mem::zeroed::<libc::flock>() };
+        flock.l_type = lock_type as libc::c_short;
+        flock.l_whence = libc::SEEK_SET as libc::c_short;
+        flock.l_start = offset as libc::off_t;
+        flock.l_len = len as libc::off_t;
+        // l_pid must be zero for open file description locks
+        if unsafe { libc::fcntl(fd, cmd, &mut flock) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    // The parts of start..end not covered by any of the given ranges
+    pub(super) fn uncovered(
+        start: usize,
+        end: usize,
+        mut covered: Vec<(usize, usize)>,
+    ) -> Vec<(usize, usize)> {
+        covered.sort_unstable();
+        let mut gaps = Vec::new();
+        let mut pos = start;
+        for (s, e) in covered {
+            if s >= end {
+                break;
+            }
+            if s > pos {
+                gaps.push((pos, s));
+            }
+            pos = pos.max(e);
+        }
+        if pos < end {
+            gaps.push((pos, end));
+        }
+        gaps
+    }
+}
@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::sync::mpsc::Sender;
+use std::thread;
+
+use super::range_lock::LockTable;
+use super::Vmem;
+
+// What to do with a Vmem once the last Handle, HandleMut, Segment or SegmentMut referencing it has
+// been dropped. Either way the Vmem is handed back by value so that it can be recycled.
+pub enum Reclaim {
+    Callback(Box<dyn FnOnce(Vmem) + Send + Sync>),
+    Channel(Sender<Vmem>),
+}
+
+impl fmt::Debug for Reclaim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Callback(_) => f.write_str("Reclaim::Callback"),
+            Self::Channel(_) => f.write_str("Reclaim::Channel"),
+        }
+    }
+}
+
+// The resource shared (via Arc) by all Handles and Segments pointing to the same Vmem.
+pub(super) struct Resource {
+    vmem: ManuallyDrop<Vmem>,
+    reclaim: Option<Reclaim>,
+    pub(super) locks: LockTable,
+}
+
+impl Resource {
+    pub(super) fn new(vmem: Vmem, reclaim: Option<Reclaim>) -> Self {
+        Self {
+            vmem: ManuallyDrop::new(vmem),
+            reclaim,
+            locks: LockTable::default(),
+        }
+    }
+}
+
+impl Deref for Resource {
+    type Target = Vmem;
+    fn deref(&self) -> &Vmem {
+        &self.vmem
+    }
+}
+
+impl DerefMut for Resource {
+    fn deref_mut(&mut self) -> &mut Vmem {
+        &mut self.vmem
+    }
+}
+
+impl Drop for Resource {
+    fn drop(&mut self) {
+        // Safe because self.vmem is never accessed again after this
+        let vmem = unsafe { ManuallyDrop::take(&mut self.vmem) };
+        if let Err(e) = vmem.check_canaries() {
+            drop(vmem);
+            // Panicking while already panicking would abort
+            if !thread::panicking() {
+                panic!("{}", e);
+            }
+            return;
+        }
+        match self.reclaim.take() {
+            None => drop(vmem),
+            Some(reclaim) => {
+                // Secure memory must not leak its contents to whoever recycles it
+                vmem.wipe_secure();
+                match reclaim {
+                    Reclaim::Callback(f) => f(vmem),
+                    // If the receiving end is gone nobody is interested in the Vmem anymore and it
+                    // is dropped along with the SendError
+                    Reclaim::Channel(tx) => drop(tx.send(vmem)),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resource")
+            .field("vmem", &*self.vmem)
+            .field("reclaim", &self.reclaim)
+            .finish()
+    }
+}
@@ -2,33 +2,130 @@
 
 use std::convert::From;
 use std::ops::Deref;
+use std::os::unix::io::RawFd;
 use std::slice;
 use std::sync::Arc;
 use std::vec::Vec;
 
 use crate::err::Error;
-use crate::io_vec::IoVec;
+use crate::io_vec::{ByteRange, IoVec};
+use crate::pod::{self, Pod};
 use crate::traits;
 
+use super::advice::{self, Advice};
+use super::range_lock::{LockMode, RangeLockGuard};
+use super::resource::Resource;
+use super::vmem::helper::address_alignment;
 use super::{Handle, Vmem};
 
 #[derive(Debug, Clone)]
 pub struct Segment {
     pub(super) ptr: *const u8,
     pub(super) len: usize,
-    pub(super) vmem: Arc<Vmem>,
+    pub(super) vmem: Arc<Resource>,
+}
+
+// A Segment only ever hands out shared access to its memory (like &[u8])
+unsafe impl Send for Segment {}
+unsafe impl Sync for Segment {}
+
+impl Segment {
+    pub(crate) fn vmem(&self) -> &Vmem {
+        &self.vmem
+    }
+
+    // Like try_split() but additionally ensures that all resulting Segments start at an address
+    // and have a length that are multiples of block_size
+    pub fn try_split_aligned(
+        self,
+        io_vec: &IoVec,
+        block_size: usize,
+    ) -> Result<Vec<Self>, (Error, Self)> {
+        if let Err(e) = io_vec.check_aligned(self.len, block_size) {
+            return Err((e, self));
+        }
+        if address_alignment(self.ptr) < block_size {
+            return Err((Error::MisalignedBuffer { block_size }, self));
+        }
+        traits::Segment::try_split(self, io_vec)
+    }
+
+    // Views the memory as a slice of T, fails unless it is aligned for T and its length is a
+    // multiple of the size of T
+    pub fn as_slice<T: Pod>(&self) -> Result<&[T], Error> {
+        unsafe { pod::view(self.ptr, self.len) }
+    }
+
+    // Advisory lock on range (relative to this Segment). Contends with the locks taken through any
+    // Segment or SegmentMut of the same Vmem within this process, blocks until it is acquired. The
+    // guard derefs to the locked range.
+    pub fn lock_range(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        self.lock(range, mode, true, None)
+    }
+
+    // Like lock_range() but fails with Error::RangeLocked instead of blocking
+    pub fn try_lock_range(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        self.lock(range, mode, false, None)
+    }
+
+    // See LockTable::lock() for ofd
+    pub(crate) fn lock(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+        blocking: bool,
+        ofd: Option<RawFd>,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        let (offset, len) = range.to_offset_len(self.len)?;
+        let base_ptr = self.vmem.mut_ptr_len().0;
+        let vmem_offset = self.ptr as usize - base_ptr as usize + offset;
+        Ok(RangeLockGuard {
+            held: self
+                .vmem
+                .locks
+                .lock(vmem_offset, len, mode, blocking, ofd)?,
+            ptr: unsafe { self.ptr.add(offset) },
+            len,
+        })
+    }
+
+    // Only supported for mmap based Vmems, destructive advice only for those shared with a file
+    pub fn advise(&self, advice: Advice) -> Result<(), Error> {
+        advice::advise(&self.vmem, self.ptr, self.len, advice, false)
+    }
+
+    // Advises Advice::WillNeed for the ranges of io_vec (relative to this Segment), e.g. before
+    // handing the result of try_split() to workers
+    pub fn prefetch(&self, io_vec: &IoVec) -> Result<(), Error> {
+        for r in io_vec.iter() {
+            let (offset, len) = r.to_offset_len(self.len)?;
+            advice::advise(
+                &self.vmem,
+                self.ptr.wrapping_add(offset),
+                len,
+                Advice::WillNeed,
+                false,
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl traits::Segment for Segment {
     type Handle = Handle;
 
     fn from_handle(handle: Self::Handle) -> Self {
-        let (ptr, len) = match *handle.vmem {
-            Vmem::VecU8(ref v) => (v.as_ptr(), v.len()),
-            Vmem::AnonMmap(ref m) => (m.mut_ptr as *const u8, m.len),
-        };
+        let (mut_ptr, len) = handle.vmem.mut_ptr_len();
         Self {
-            ptr,
+            ptr: mut_ptr as *const u8,
             len,
             vmem: handle.vmem,
         }
@@ -2,32 +2,189 @@
 
 use std::any::type_name;
 use std::convert::{From, TryFrom};
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::os::unix::io::RawFd;
 use std::slice;
 use std::sync::Arc;
 use std::vec::Vec;
 
 use crate::err::Error;
-use crate::io_vec::IoVec;
+use crate::io_vec::{ByteRange, IoVec};
+use crate::pod::{self, Pod};
 use crate::traits;
 
+use super::advice::{self, Advice};
+use super::range_lock::{LockMode, RangeLockGuard, RangeLockGuardMut};
+use super::resource::Resource;
+use super::vmem::helper::address_alignment;
 use super::{HandleMut, Vmem};
 
 #[derive(Debug)]
 pub struct SegmentMut {
     pub(super) mut_ptr: *mut u8,
     pub(super) len: usize,
-    pub(super) vmem: Arc<Vmem>,
+    pub(super) vmem: Arc<Resource>,
+}
+
+// The memory of a SegmentMut doesn't overlap with that of any other Segment or SegmentMut, so it
+// behaves like a &mut [u8]
+unsafe impl Send for SegmentMut {}
+unsafe impl Sync for SegmentMut {}
+
+impl SegmentMut {
+    pub(crate) fn vmem(&self) -> &Vmem {
+        &self.vmem
+    }
+
+    // Like try_split() but additionally ensures that all resulting SegmentMuts start at an address
+    // and have a length that are multiples of block_size
+    pub fn try_split_aligned(
+        self,
+        io_vec: &IoVec,
+        block_size: usize,
+    ) -> Result<Vec<Self>, (Error, Self)> {
+        if let Err(e) = io_vec.check_aligned(self.len, block_size) {
+            return Err((e, self));
+        }
+        if address_alignment(self.mut_ptr) < block_size {
+            return Err((Error::MisalignedBuffer { block_size }, self));
+        }
+        traits::SegmentMut::try_split(self, io_vec)
+    }
+
+    // Views the memory as a slice of T, fails unless it is aligned for T and its length is a
+    // multiple of the size of T
+    pub fn as_slice<T: Pod>(&self) -> Result<&[T], Error> {
+        unsafe { pod::view(self.mut_ptr, self.len) }
+    }
+
+    pub fn as_mut_slice<T: Pod>(&mut self) -> Result<&mut [T], Error> {
+        unsafe { pod::view_mut(self.mut_ptr, self.len) }
+    }
+
+    // Advisory lock on range (relative to this SegmentMut). Contends with the locks taken through
+    // any Segment or SegmentMut of the same Vmem within this process, blocks until it's acquired.
+    // The guard derefs to the locked range, use lock_range_mut() to write to it.
+    pub fn lock_range(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        self.lock(range, mode, true, None)
+    }
+
+    // Like lock_range() but fails with Error::RangeLocked instead of blocking
+    pub fn try_lock_range(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        self.lock(range, mode, false, None)
+    }
+
+    // Exclusive lock on range that allows writing to it through the guard
+    pub fn lock_range_mut(&mut self, range: &ByteRange) -> Result<RangeLockGuardMut<'_>, Error> {
+        self.lock_mut(range, true, None)
+    }
+
+    // Like lock_range_mut() but fails with Error::RangeLocked instead of blocking
+    pub fn try_lock_range_mut(
+        &mut self,
+        range: &ByteRange,
+    ) -> Result<RangeLockGuardMut<'_>, Error> {
+        self.lock_mut(range, false, None)
+    }
+
+    // See LockTable::lock() for ofd
+    pub(crate) fn lock(
+        &self,
+        range: &ByteRange,
+        mode: LockMode,
+        blocking: bool,
+        ofd: Option<RawFd>,
+    ) -> Result<RangeLockGuard<'_>, Error> {
+        let (offset, len, vmem_offset) = self.vmem_offset_len(range)?;
+        Ok(RangeLockGuard {
+            held: self
+                .vmem
+                .locks
+                .lock(vmem_offset, len, mode, blocking, ofd)?,
+            ptr: unsafe { self.mut_ptr.add(offset) },
+            len,
+        })
+    }
+
+    pub(crate) fn lock_mut(
+        &mut self,
+        range: &ByteRange,
+        blocking: bool,
+        ofd: Option<RawFd>,
+    ) -> Result<RangeLockGuardMut<'_>, Error> {
+        let (offset, len, vmem_offset) = self.vmem_offset_len(range)?;
+        Ok(RangeLockGuardMut {
+            held: self
+                .vmem
+                .locks
+                .lock(vmem_offset, len, LockMode::Exclusive, blocking, ofd)?,
+            mut_ptr: unsafe { self.mut_ptr.add(offset) },
+            len,
+            _marker: PhantomData,
+        })
+    }
+
+    // Offset and length of range within this SegmentMut, and its offset within the Vmem
+    fn vmem_offset_len(&self, range: &ByteRange) -> Result<(usize, usize, usize), Error> {
+        let (offset, len) = range.to_offset_len(self.len)?;
+        let base_ptr = self.vmem.mut_ptr_len().0;
+        Ok((
+            offset,
+            len,
+            self.mut_ptr as usize - base_ptr as usize + offset,
+        ))
+    }
+
+    // Only supported for mmap based Vmems. Destructive advice is only accepted for memory shared
+    // with a file (which keeps its contents), use discard() for private memory.
+    pub fn advise(&self, advice: Advice) -> Result<(), Error> {
+        advice::advise(&self.vmem, self.mut_ptr, self.len, advice, false)
+    }
+
+    // Applies destructive advice (Advice::DontNeed or Advice::Free), which may zero private memory
+    // and therefore needs exclusive access. Only affects pages completely covered by this
+    // SegmentMut.
+    pub fn discard(&mut self, advice: Advice) -> Result<(), Error> {
+        if !advice.is_destructive() {
+            return Err(Error::UnsupportedOperation {
+                operation: format!("discard({:?})", advice),
+                reason: "Advice is not destructive, use advise()".to_string(),
+            });
+        }
+        advice::advise(&self.vmem, self.mut_ptr, self.len, advice, true)
+    }
+
+    // Advises Advice::WillNeed for the ranges of io_vec (relative to this SegmentMut), e.g. before
+    // handing the result of try_split() to workers
+    pub fn prefetch(&self, io_vec: &IoVec) -> Result<(), Error> {
+        for r in io_vec.iter() {
+            let (offset, len) = r.to_offset_len(self.len)?;
+            advice::advise(
+                &self.vmem,
+                self.mut_ptr.wrapping_add(offset),
+                len,
+                Advice::WillNeed,
+                false,
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl traits::SegmentMut for SegmentMut {
     type HandleMut = HandleMut;
 
     fn from_handle_mut(handle_mut: Self::HandleMut) -> Self {
-        let (mut_ptr, len) = match *handle_mut.vmem {
-            Vmem::VecU8(ref v) => (v.as_ptr() as *mut u8, v.len()),
-            Vmem::AnonMmap(ref m) => (m.mut_ptr as *mut u8, m.len),
-        };
+        let (mut_ptr, len) = handle_mut.vmem.mut_ptr_len();
         Self {
             mut_ptr,
             len,
@@ -60,7 +217,7 @@ impl traits::SegmentMut for SegmentMut {
             ))
         } else if !v
             .windows(2)
-            .all(|w| Arc::<Vmem>::ptr_eq(&w[0].vmem, &w[1].vmem))
+            .all(|w| Arc::<Resource>::ptr_eq(&w[0].vmem, &w[1].vmem))
         {
             Err((
                 Error::ConversionFailed {
@@ -71,7 +228,7 @@ impl traits::SegmentMut for SegmentMut {
                 },
                 v,
             ))
-        } else if !Arc::<Vmem>::strong_count(&v[0].vmem) == v.len() {
+        } else if Arc::<Resource>::strong_count(&v[0].vmem) != v.len() {
             Err((
                 Error::ConversionFailed {
                     from_type: type_name::<Vec<Self>>().to_string(),
@@ -87,7 +244,7 @@ impl traits::SegmentMut for SegmentMut {
             let x = v.pop().unwrap();
             drop(v);
             assert!(
-                Arc::<Vmem>::strong_count(&x.vmem) == 1,
+                Arc::<Resource>::strong_count(&x.vmem) == 1,
                 "Spurious outstanding vmem::SegmentMut detected after dropping all but one \
                      vmem::SegmentMut in vector of segments."
             );
@@ -105,7 +262,7 @@ impl traits::SegmentMut for SegmentMut {
             Err(e) => Err((e, self)),
             Ok(true) => Err((
                 Error::OverlappingIoVec {
-                    outer_len: self.len.clone(),
+                    outer_len: self.len,
                 },
                 self,
             )),
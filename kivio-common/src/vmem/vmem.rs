@@ -1,21 +1,137 @@
 // SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
 
+use std::alloc::{self, Layout};
+use std::fmt;
+use std::ptr::NonNull;
 use std::vec::Vec;
 
+use crate::err::Error;
+use crate::traits::BackendKind;
+
+use super::{AnonMmap, Lazy, Loader, Memfd};
+
 pub type VecU8 = Vec<u8>;
 
-#[derive(Debug)]
-pub struct AnonMmap {
-    pub mut_ptr: *mut u8,
-    pub len: usize,
+pub type BoxU8 = Box<[u8]>;
+
+pub type StaticU8 = &'static [u8];
+
+// Zero-initialized heap memory whose start address is a multiple of align (e.g. for O_DIRECT)
+pub struct Aligned {
+    mut_ptr: *mut u8,
+    len: usize,
+    align: usize,
+}
+
+impl Aligned {
+    pub fn new(len: usize, align: usize) -> Result<Self, Error> {
+        let layout =
+            Layout::from_size_align(len, align).map_err(|_| Error::InvalidAlignment { align })?;
+        let mut_ptr = if len == 0 {
+            // Zero sized allocations are not allowed, but a dangling pointer is fine for a zero
+            // length slice (and happens to be aligned to align)
+            NonNull::<u8>::dangling().as_ptr().wrapping_add(align - 1)
+        } else {
+            let p = unsafe { alloc::alloc_zeroed(layout) };
+            if p.is_null() {
+                alloc::handle_alloc_error(layout)
+            }
+            p
+        };
+        Ok(Self {
+            mut_ptr,
+            len,
+            align,
+        })
+    }
 }
 
-// TODO need to implement drop and some constructors on Mmap
+// The allocation is owned by Aligned and only ever accessed through Handles and Segments, which
+// uphold the aliasing rules themselves.
+unsafe impl Send for Aligned {}
+unsafe impl Sync for Aligned {}
+
+impl Drop for Aligned {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            unsafe {
+                alloc::dealloc(
+                    self.mut_ptr,
+                    Layout::from_size_align_unchecked(self.len, self.align),
+                )
+            }
+        }
+    }
+}
+
+// Memory owned by someone else (e.g. a C library or a different allocator). The drop function is
+// called with the original pointer and length once the last reference is gone.
+pub struct Foreign {
+    mut_ptr: *mut u8,
+    len: usize,
+    read_only: bool,
+    drop_fn: Option<Box<dyn FnOnce(*mut u8, usize) + Send + Sync>>,
+}
+
+impl Foreign {
+    /// # Safety
+    ///
+    /// `mut_ptr` must be valid for reads and writes of `len` bytes until `drop_fn` is called and
+    /// must not be accessed by anyone else in the meantime.
+    pub unsafe fn new<F>(mut_ptr: *mut u8, len: usize, drop_fn: F) -> Self
+    where
+        F: FnOnce(*mut u8, usize) + Send + Sync + 'static,
+    {
+        Self {
+            mut_ptr,
+            len,
+            read_only: false,
+            drop_fn: Some(Box::new(drop_fn)),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reads of `len` bytes until `drop_fn` is called and must not be
+    /// written to in the meantime.
+    pub unsafe fn new_read_only<F>(ptr: *const u8, len: usize, drop_fn: F) -> Self
+    where
+        F: FnOnce(*mut u8, usize) + Send + Sync + 'static,
+    {
+        Self {
+            mut_ptr: ptr as *mut u8,
+            len,
+            read_only: true,
+            drop_fn: Some(Box::new(drop_fn)),
+        }
+    }
+}
+
+// See Aligned
+unsafe impl Send for Foreign {}
+unsafe impl Sync for Foreign {}
+
+impl Drop for Foreign {
+    fn drop(&mut self) {
+        if let Some(f) = self.drop_fn.take() {
+            f(self.mut_ptr, self.len)
+        }
+    }
+}
 
-#[derive(Debug)]
 pub enum Vmem {
     VecU8(VecU8),
+    BoxU8(BoxU8),
+    StaticU8(StaticU8),
+    #[cfg(feature = "bytes")]
+    Bytes(bytes::Bytes),
+    #[cfg(feature = "bytes")]
+    BytesMut(bytes::BytesMut),
+    Aligned(Aligned),
     AnonMmap(AnonMmap),
+    Memfd(Memfd),
+    Lazy(Lazy),
+    Foreign(Foreign),
 }
 
 impl Vmem {
@@ -27,14 +143,189 @@ impl Vmem {
         Self::VecU8(vec)
     }
 
+    pub fn new_aligned(len: usize, align: usize) -> Result<Self, Error> {
+        Ok(Self::Aligned(Aligned::new(len, align)?))
+    }
+
+    pub fn from_box_u8(b: BoxU8) -> Self {
+        Self::BoxU8(b)
+    }
+
+    // Read-only, use with vmem::Handle::from_vmem()
+    pub fn from_static_u8(s: StaticU8) -> Self {
+        Self::StaticU8(s)
+    }
+
+    // Read-only, use with vmem::Handle::from_vmem()
+    #[cfg(feature = "bytes")]
+    pub fn from_bytes(b: bytes::Bytes) -> Self {
+        Self::Bytes(b)
+    }
+
+    #[cfg(feature = "bytes")]
+    pub fn from_bytes_mut(b: bytes::BytesMut) -> Self {
+        Self::BytesMut(b)
+    }
+
+    pub fn new_anon_mmap(len: usize) -> Result<Self, Error> {
+        Ok(Self::AnonMmap(AnonMmap::new(len)?))
+    }
+
     pub fn from_anon_mmap(anon_mmap: AnonMmap) -> Self {
         Self::AnonMmap(anon_mmap)
     }
 
+    pub fn new_memfd(name: &str, len: usize) -> Result<Self, Error> {
+        Ok(Self::Memfd(Memfd::new(name, len)?))
+    }
+
+    pub fn from_memfd(memfd: Memfd) -> Self {
+        Self::Memfd(memfd)
+    }
+
+    pub fn new_lazy(len: usize, loader: Loader) -> Result<Self, Error> {
+        Ok(Self::Lazy(Lazy::new(len, loader)?))
+    }
+
+    pub fn from_lazy(lazy: Lazy) -> Self {
+        Self::Lazy(lazy)
+    }
+
+    pub fn from_foreign(foreign: Foreign) -> Self {
+        Self::Foreign(foreign)
+    }
+
+    pub fn len(&self) -> usize {
+        self.mut_ptr_len().1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        match self {
+            Self::VecU8(ref v) => v.capacity(),
+            #[cfg(feature = "bytes")]
+            Self::BytesMut(ref b) => b.capacity(),
+            Self::AnonMmap(ref m) => m.data_len(),
+            Self::Memfd(ref m) => m.map_len(),
+            Self::Lazy(ref l) => l.map_len(),
+            _ => self.len(),
+        }
+    }
+
+    pub fn backend_kind(&self) -> BackendKind {
+        match self {
+            Self::VecU8(_) => BackendKind::VecU8,
+            Self::BoxU8(_) => BackendKind::BoxU8,
+            Self::StaticU8(_) => BackendKind::StaticU8,
+            #[cfg(feature = "bytes")]
+            Self::Bytes(_) => BackendKind::Bytes,
+            #[cfg(feature = "bytes")]
+            Self::BytesMut(_) => BackendKind::BytesMut,
+            Self::Aligned(_) => BackendKind::Aligned,
+            Self::AnonMmap(_) => BackendKind::AnonMmap,
+            Self::Memfd(_) => BackendKind::Memfd,
+            Self::Lazy(_) => BackendKind::Lazy,
+            Self::Foreign(_) => BackendKind::Foreign,
+        }
+    }
+
+    // Largest power of two the start address is a multiple of (at least the alignment requested
+    // when using new_aligned())
+    pub fn alignment(&self) -> usize {
+        match self {
+            Self::Aligned(ref a) => a.align,
+            _ => helper::address_alignment(self.mut_ptr_len().0),
+        }
+    }
+
+    // Read-only memory can only ever be accessed through a vmem::Handle (and never a HandleMut)
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            Self::StaticU8(_) => true,
+            #[cfg(feature = "bytes")]
+            Self::Bytes(_) => true,
+            Self::Memfd(ref m) => m.read_only,
+            Self::Foreign(ref f) => f.read_only,
+            _ => false,
+        }
+    }
+
+    // True if writes to the memory actually fault (see AnonMmapOptions::enforce_protection)
+    pub fn is_protected(&self) -> bool {
+        match self {
+            Self::AnonMmap(ref m) => m.is_protected(),
+            _ => false,
+        }
+    }
+
+    // Checks the canaries of AnonMmaps created with AnonMmapOptions::guard_pages, otherwise this is
+    // a no-op
+    pub fn check_canaries(&self) -> Result<(), Error> {
+        match self {
+            Self::AnonMmap(ref m) => m.check_canaries(),
+            _ => Ok(()),
+        }
+    }
+
+    // Overwrites AnonMmaps created with AnonMmapOptions::secure with zeros, otherwise this is a
+    // no-op
+    pub(super) fn wipe_secure(&self) {
+        if let Self::AnonMmap(ref m) = self {
+            m.wipe()
+        }
+    }
+
+    // Flips the page protections of the underlying memory if that is supported and requested
+    // (see AnonMmapOptions::enforce_protection), otherwise this is a no-op
+    pub(super) fn protect(&self, read_only: bool) -> Result<(), Error> {
+        match self {
+            Self::AnonMmap(ref m) => m.protect(read_only),
+            _ => Ok(()),
+        }
+    }
+
     pub(crate) fn mut_ptr_len(&self) -> (*mut u8, usize) {
         match self {
             Self::VecU8(ref v) => (v.as_ptr() as *mut u8, v.len()),
+            Self::BoxU8(ref b) => (b.as_ptr() as *mut u8, b.len()),
+            Self::StaticU8(s) => (s.as_ptr() as *mut u8, s.len()),
+            #[cfg(feature = "bytes")]
+            Self::Bytes(ref b) => (b.as_ptr() as *mut u8, b.len()),
+            #[cfg(feature = "bytes")]
+            Self::BytesMut(ref b) => (b.as_ptr() as *mut u8, b.len()),
+            Self::Aligned(ref a) => (a.mut_ptr, a.len),
             Self::AnonMmap(ref m) => (m.mut_ptr, m.len),
+            Self::Memfd(ref m) => (m.mut_ptr, m.len),
+            Self::Lazy(ref l) => (l.mut_ptr, l.len),
+            Self::Foreign(ref f) => (f.mut_ptr, f.len),
+        }
+    }
+}
+
+// Only report metadata: dumping the contents (or raw pointers) of potentially large buffers is
+// never what one wants in a log message.
+impl fmt::Debug for Vmem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vmem")
+            .field("backend_kind", &self.backend_kind())
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+pub(super) mod helper {
+
+    // Largest power of two dividing the address of ptr
+    pub(crate) fn address_alignment(ptr: *const u8) -> usize {
+        let addr = ptr as usize;
+        if addr == 0 {
+            1 << (usize::BITS - 1)
+        } else {
+            1 << addr.trailing_zeros()
         }
     }
 }
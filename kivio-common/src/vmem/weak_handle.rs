@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::convert::From;
+use std::fmt;
+use std::sync::Weak;
+
+use crate::traits;
+
+use super::resource::Resource;
+use super::Handle;
+
+// A WeakHandle does not keep the Vmem alive. Upgrading fails once all Handles and Segments are
+// gone or once the Vmem has been converted back into a HandleMut.
+#[derive(Clone)]
+pub struct WeakHandle {
+    pub(super) vmem: Weak<Resource>,
+}
+
+impl traits::WeakHandle for WeakHandle {
+    type Handle = Handle;
+
+    fn from_handle(handle: &Self::Handle) -> Self {
+        handle.downgrade()
+    }
+
+    fn upgrade(&self) -> Option<Self::Handle> {
+        self.vmem.upgrade().map(|vmem| Handle { vmem })
+    }
+
+    fn outstanding_refs(&self) -> usize {
+        self.vmem.strong_count()
+    }
+}
+
+impl From<&Handle> for WeakHandle {
+    fn from(item: &Handle) -> Self {
+        item.downgrade()
+    }
+}
+
+impl fmt::Debug for WeakHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakHandle")
+            .field("outstanding_refs", &self.vmem.strong_count())
+            .finish()
+    }
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use kivio_common::err::Error;
+use kivio_common::{io_vec, memfd, vmem, Handle, HandleMut, Segment, SegmentMut};
+
+#[test]
+fn test_advice() {
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let len = 4 * page_size;
+    let iov = io_vec::IoVec::from_chunk_size(len, page_size + page_size / 2);
+
+    // Non-mmap based memory doesn't support advice
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::new_vec_u8(len)).unwrap();
+    let sm = vmem::SegmentMut::from_handle_mut(hm);
+    assert!(matches!(
+        sm.advise(vmem::Advice::WillNeed),
+        Err(Error::UnsupportedOperation { .. })
+    ));
+
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::new_anon_mmap(len).unwrap()).unwrap();
+    let mut sm = vmem::SegmentMut::from_handle_mut(hm);
+    sm.fill(1);
+    sm.prefetch(&iov).unwrap();
+    let mut vsm = sm.try_split(&iov).unwrap();
+    for s in vsm.iter() {
+        s.advise(vmem::Advice::Sequential).unwrap();
+    }
+
+    // Discarding needs exclusive access and only affects the pages completely covered by the
+    // SegmentMut
+    assert!(matches!(
+        vsm[1].advise(vmem::Advice::DontNeed),
+        Err(Error::UnsupportedOperation { .. })
+    ));
+    assert!(matches!(
+        vsm[1].discard(vmem::Advice::WillNeed),
+        Err(Error::UnsupportedOperation { .. })
+    ));
+    vsm[1].discard(vmem::Advice::DontNeed).unwrap();
+    let sm = vmem::SegmentMut::try_from_vec_segment_mut(vsm).unwrap();
+    assert!(sm[..2 * page_size].iter().all(|&b| b == 1));
+    assert!(sm[2 * page_size..3 * page_size].iter().all(|&b| b == 0));
+    assert!(sm[3 * page_size..].iter().all(|&b| b == 1));
+
+    // Private memory can't be discarded through a Segment
+    let hm = vmem::HandleMut::try_from_segment_mut(sm).unwrap();
+    let s = vmem::Segment::from_handle(vmem::Handle::from_handle_mut(hm));
+    s.advise(vmem::Advice::Random).unwrap();
+    assert!(matches!(
+        s.advise(vmem::Advice::DontNeed),
+        Err(Error::UnsupportedOperation { .. })
+    ));
+
+    // ... while shared memory keeps its contents
+    let hm = memfd::HandleMut::new("kivio-test", len).unwrap();
+    let mut sm = memfd::SegmentMut::from_handle_mut(hm);
+    sm.fill(1);
+    let hm = memfd::HandleMut::try_from_segment_mut(sm).unwrap();
+    let s = memfd::Segment::from_handle(memfd::Handle::from_handle_mut(hm));
+    s.advise(vmem::Advice::DontNeed).unwrap();
+    assert!(s.iter().all(|&b| b == 1));
+}
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use kivio_common::err::Error;
+use kivio_common::{io_vec, vmem, BackendKind, HandleMut, SegmentMut};
+
+#[test]
+fn test_aligned() {
+    assert!(matches!(
+        vmem::Vmem::new_aligned(4096, 3),
+        Err(Error::InvalidAlignment { align: 3 })
+    ));
+
+    let v = vmem::Vmem::new_aligned(4 * 4096, 4096).unwrap();
+    assert!(v.alignment() >= 4096);
+    let hm = vmem::HandleMut::try_from_vmem(v).unwrap();
+    assert_eq!(hm.backend_kind(), BackendKind::Aligned);
+    assert_eq!(hm.len(), 4 * 4096);
+
+    // Plans that don't respect the block size are rejected
+    let iov = io_vec::IoVec::from_chunk_size(4 * 4096, 1000);
+    assert!(matches!(
+        iov.check_aligned(4 * 4096, 512),
+        Err(Error::MisalignedIoVec { .. })
+    ));
+    let sm = vmem::SegmentMut::from_handle_mut(hm);
+    let sm = sm.try_split_aligned(&iov, 512).err().unwrap().1;
+
+    // ... while aligned ones result in aligned segments
+    let iov = io_vec::IoVec::from_chunk_size(4 * 4096, 4096);
+    let vsm = sm.try_split_aligned(&iov, 4096).unwrap();
+    for s in vsm.iter() {
+        assert_eq!(s.as_ptr() as usize % 4096, 0);
+        assert_eq!(s.len(), 4096);
+    }
+
+    // Splitting again at a finer granularity doesn't work if the block size is larger
+    let sm = vmem::SegmentMut::try_from_vec_segment_mut(vsm).unwrap();
+    let vsm = sm
+        .try_split(&io_vec::IoVec::from_chunk_size(4 * 4096, 512))
+        .unwrap();
+    let iov = io_vec::IoVec::from_chunk_size(512, 512);
+    let r = vsm
+        .into_iter()
+        .nth(1)
+        .unwrap()
+        .try_split_aligned(&iov, 4096);
+    assert!(matches!(r, Err((Error::MisalignedIoVec { .. }, _))));
+}
@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::slice;
+use std::sync::mpsc::channel;
+
+use kivio_common::{vmem, BackendKind, Handle, HandleMut, SegmentMut, VmemSegmentMut};
+
+// Runs f in a forked child and returns the signal that terminated it (Err) or its exit code (Ok)
+fn run_in_child<F: FnOnce() -> i32>(f: F) -> Result<i32, i32> {
+    match unsafe { libc::fork() } {
+        0 => unsafe { libc::_exit(f()) },
+        pid => {
+            let mut status = 0;
+            assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+            if libc::WIFSIGNALED(status) {
+                Err(libc::WTERMSIG(status))
+            } else {
+                Ok(libc::WEXITSTATUS(status))
+            }
+        }
+    }
+}
+
+fn signal_in_child<F: FnOnce()>(f: F) -> Option<i32> {
+    run_in_child(|| {
+        f();
+        0
+    })
+    .err()
+}
+
+#[test]
+fn test_anon_mmap_protection() {
+    let options = vmem::AnonMmapOptions {
+        enforce_protection: true,
+        ..Default::default()
+    };
+    let m = vmem::AnonMmap::with_options(10, options).unwrap();
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::from_anon_mmap(m)).unwrap();
+    assert_eq!(hm.backend_kind(), BackendKind::AnonMmap);
+    assert_eq!(hm.len(), 10);
+    assert!(hm.capacity() >= 10);
+
+    let mut sm = vmem::SegmentMut::from_handle_mut(hm);
+    sm[0] = b'a';
+    let (mut_ptr, _) = sm.mut_ptr_len();
+    let hm = vmem::HandleMut::try_from_segment_mut(sm).unwrap();
+
+    // Writing through the leaked pointer faults while the memory is behind a Handle...
+    let h = vmem::Handle::from_handle_mut(hm);
+    assert!(h.is_protected());
+    assert_eq!(
+        signal_in_child(|| unsafe { mut_ptr.write_volatile(b'b') }),
+        Some(libc::SIGSEGV)
+    );
+
+    // ... but works again once it's converted back
+    let hm = vmem::HandleMut::try_from_handle(h).unwrap();
+    assert_eq!(
+        signal_in_child(|| unsafe { mut_ptr.write_volatile(b'b') }),
+        None
+    );
+    let sm = vmem::SegmentMut::from_handle_mut(hm);
+    assert_eq!(sm[0], b'a');
+
+    // If the protection can't be enforced (here because part of the mapping is gone, which makes
+    // mprotect() fail) the conversion either fails or leaves the memory writable, which panics in
+    // debug builds
+    let hm = vmem::HandleMut::try_from_segment_mut(sm).unwrap();
+    let r = run_in_child(|| {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        unsafe { libc::munmap(mut_ptr as *mut libc::c_void, page_size) };
+        let (_, hm) = vmem::Handle::try_from_handle_mut(hm).unwrap_err();
+        match catch_unwind(AssertUnwindSafe(|| vmem::Handle::from_handle_mut(hm))) {
+            Ok(h) if !cfg!(debug_assertions) && !h.is_protected() => 0,
+            Err(_) if cfg!(debug_assertions) => 0,
+            _ => 1,
+        }
+    });
+    assert_eq!(r, Ok(0));
+}
+
+#[test]
+fn test_anon_mmap_guard_pages() {
+    let options = vmem::AnonMmapOptions {
+        guard_pages: true,
+        ..Default::default()
+    };
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let m = vmem::AnonMmap::with_options(10, options).unwrap();
+    assert!(m.check_canaries().is_ok());
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::from_anon_mmap(m)).unwrap();
+    let sm = vmem::SegmentMut::from_handle_mut(hm);
+    let (mut_ptr, len) = sm.mut_ptr_len();
+
+    // Accessing the guard pages faults
+    assert_eq!(
+        signal_in_child(|| unsafe { mut_ptr.sub(1).write_volatile(b'x') }),
+        Some(libc::SIGSEGV)
+    );
+    assert_eq!(
+        signal_in_child(|| unsafe { mut_ptr.add(page_size).write_volatile(b'x') }),
+        Some(libc::SIGSEGV)
+    );
+
+    // Off-by-one writes into the slack before the guard page are caught once the memory is
+    // released
+    unsafe { mut_ptr.add(len).write_volatile(b'x') };
+    let r = catch_unwind(AssertUnwindSafe(|| drop(sm)));
+    assert!(r.is_err());
+}
+
+#[test]
+fn test_anon_mmap_secure() {
+    let options = vmem::AnonMmapOptions {
+        secure: true,
+        ..Default::default()
+    };
+    let m = vmem::AnonMmap::with_options(10, options).unwrap();
+    let (tx, rx) = channel();
+    let hm = vmem::HandleMut::try_from_vmem_with_reclaim(
+        vmem::Vmem::from_anon_mmap(m),
+        vmem::Reclaim::Channel(tx),
+    )
+    .unwrap();
+    let mut sm = vmem::SegmentMut::from_handle_mut(hm);
+    sm[9] = 42;
+    let (mut_ptr, _) = sm.mut_ptr_len();
+
+    // Forked children only ever see zeros
+    assert_eq!(
+        run_in_child(|| unsafe { mut_ptr.add(9).read_volatile() } as i32),
+        Ok(0)
+    );
+    assert_eq!(sm[9], 42);
+
+    // The contents are wiped before the memory is handed back for recycling
+    drop(sm);
+    let hm = vmem::HandleMut::try_from_vmem(rx.try_recv().unwrap()).unwrap();
+    let sm = vmem::SegmentMut::from_handle_mut(hm);
+    assert!(sm.iter().all(|&b| b == 0));
+}
+
+#[test]
+fn test_anon_mmap_secure_protected() {
+    let options = vmem::AnonMmapOptions {
+        enforce_protection: true,
+        secure: true,
+        ..Default::default()
+    };
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let m = vmem::AnonMmap::with_options(2 * page_size, options).unwrap();
+
+    // Wiping doesn't panic (in drop()) if the memory can't be made writable again (here because
+    // part of the mapping is gone), the remaining pages are discarded instead
+    let r = run_in_child(|| {
+        let (tx, rx) = channel();
+        let hm = vmem::HandleMut::try_from_vmem_with_reclaim(
+            vmem::Vmem::from_anon_mmap(m),
+            vmem::Reclaim::Channel(tx),
+        )
+        .unwrap();
+        let mut sm = vmem::SegmentMut::from_handle_mut(hm);
+        sm.fill(42);
+        let (mut_ptr, _) = sm.mut_ptr_len();
+        let hm = vmem::HandleMut::try_from_segment_mut(sm).unwrap();
+        let h = vmem::Handle::try_from_handle_mut(hm).unwrap();
+        unsafe { libc::munmap(mut_ptr as *mut libc::c_void, page_size) };
+        drop(h);
+        // The reclaimed Vmem can't be protected anymore either, so read the rest of it directly
+        let _vmem = rx.try_recv().unwrap();
+        let s = unsafe { slice::from_raw_parts(mut_ptr.add(page_size), page_size) };
+        s.iter().all(|&b| b == 0) as i32
+    });
+    assert_eq!(r, Ok(1));
+}
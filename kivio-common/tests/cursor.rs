@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::io::{BufRead, ErrorKind, Read, Seek, SeekFrom, Write};
+
+use kivio_common::cursor::{SegmentReader, SegmentWriter};
+use kivio_common::{io_vec, vmem, Handle, HandleMut, Segment, SegmentMut};
+
+#[test]
+fn test_cursor() {
+    // Records of a u32 (little-endian) followed by a f64 (big-endian), written across SegmentMuts
+    // whose boundaries don't line up with the records
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::new_vec_u8(36)).unwrap();
+    let vsm = vmem::SegmentMut::from_handle_mut(hm)
+        .try_split(&io_vec::IoVec::from_chunk_size(36, 5))
+        .unwrap();
+    let mut w = SegmentWriter::from_vec(vsm);
+    assert_eq!(w.len(), 36);
+    for i in 0..3 {
+        w.put_u32_le(i).unwrap();
+        w.put_f64_be(i as f64 / 2.0).unwrap();
+    }
+    assert_eq!(w.position(), 36);
+
+    // The stream doesn't grow
+    assert_eq!(w.write(b"x").unwrap(), 0);
+    assert_eq!(w.put_u8(1).unwrap_err().kind(), ErrorKind::WriteZero);
+
+    w.seek(SeekFrom::Start(12)).unwrap();
+    w.put_u32_le(42).unwrap();
+
+    let sm = vmem::SegmentMut::try_from_vec_segment_mut(w.into_inner()).unwrap();
+    assert_eq!(&sm[..4], &[0, 0, 0, 0]);
+    assert_eq!(&sm[12..16], &[42, 0, 0, 0]);
+
+    let h = vmem::Handle::from_handle_mut(vmem::HandleMut::try_from_segment_mut(sm).unwrap());
+    let vs = vmem::Segment::from_handle(h)
+        .try_split(&io_vec::IoVec::from_chunk_size(36, 7))
+        .unwrap();
+    let mut r = SegmentReader::from_vec(vs);
+    assert_eq!(r.get_u32_le().unwrap(), 0);
+    assert_eq!(r.get_f64_be().unwrap(), 0.0);
+    assert_eq!(r.get_u32_le().unwrap(), 42);
+    assert_eq!(r.get_f64_be().unwrap(), 0.5);
+
+    // Seeking relative to the end and the current position
+    r.seek(SeekFrom::End(-8)).unwrap();
+    assert_eq!(r.get_f64_be().unwrap(), 1.0);
+    assert_eq!(r.get_u8().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert!(r.seek(SeekFrom::Current(-37)).is_err());
+    r.seek(SeekFrom::Current(-12)).unwrap();
+    assert_eq!(r.get_u32_le().unwrap(), 2);
+
+    // BufRead hands out one Segment at a time
+    r.rewind().unwrap();
+    assert_eq!(r.fill_buf().unwrap().len(), 7);
+    r.consume(3);
+    assert_eq!(r.fill_buf().unwrap().len(), 4);
+    let mut rest = Vec::new();
+    r.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest.len(), 33);
+}
+
+#[test]
+fn test_cursor_single_segment() {
+    let h = vmem::Handle::from_vmem(vmem::Vmem::from_static_u8(b"kivio\nrecords\n"));
+    let r = SegmentReader::new(vmem::Segment::from_handle(h));
+    let lines: Vec<String> = r.lines().map(|l| l.unwrap()).collect();
+    assert_eq!(lines, vec!["kivio", "records"]);
+}
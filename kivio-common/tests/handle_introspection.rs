@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use kivio_common::{io_vec, vmem, BackendKind, Handle, HandleMut, Segment};
+
+#[test]
+fn test_handle_introspection() {
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::new_vec_u8(4)).unwrap();
+    assert_eq!(hm.len(), 4);
+    assert!(!hm.is_empty());
+    assert!(hm.capacity() >= 4);
+    assert_eq!(hm.backend_kind(), BackendKind::VecU8);
+    assert_eq!(hm.outstanding_refs(), 0);
+
+    let h = vmem::Handle::from_handle_mut(hm);
+    let vs = vmem::Segment::from_handle(h.clone())
+        .try_split(&io_vec::IoVec::from_chunk_size(4, 2))
+        .unwrap();
+    assert_eq!(h.len(), 4);
+    assert_eq!(h.outstanding_refs(), 2);
+
+    // Debug output must only contain metadata, never raw pointers or contents
+    assert_eq!(
+        format!("{:?}", h),
+        "Handle { backend_kind: VecU8, len: 4, outstanding_refs: 2 }"
+    );
+
+    drop(vs);
+    assert_eq!(h.outstanding_refs(), 0);
+    assert!(vmem::HandleMut::try_from_handle(h).is_ok());
+}
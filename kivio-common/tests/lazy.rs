@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use kivio_common::{io_vec, vmem, BackendKind, Handle, Segment, SegmentMut};
+
+type Loaded = Arc<Mutex<Vec<(usize, usize)>>>;
+
+// The tests only access the memory from user space, so they also work for unprivileged processes.
+// If userfaultfd is not available at all (e.g. disabled by a seccomp profile) the tests fail unless
+// KIVIO_SKIP_USERFAULTFD_TESTS is set, in which case they are skipped (None is returned).
+fn new_lazy(len: usize) -> Option<(vmem::Vmem, Loaded)> {
+    let loaded = Loaded::default();
+    let loaded_clone = loaded.clone();
+    let loader = Box::new(move |offset: usize, buf: &mut [u8]| {
+        loaded_clone.lock().unwrap().push((offset, buf.len()));
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = ((offset + i) % 251) as u8;
+        }
+    });
+    let options = vmem::LazyOptions {
+        user_mode_only: true,
+    };
+    match vmem::Lazy::with_options(len, loader, options) {
+        Ok(lazy) => Some((vmem::Vmem::from_lazy(lazy), loaded)),
+        Err(e) if env::var_os("KIVIO_SKIP_USERFAULTFD_TESTS").is_some() => {
+            eprintln!("skipped, userfaultfd is not available: {e}");
+            None
+        }
+        Err(e) => {
+            panic!("userfaultfd is not available (set KIVIO_SKIP_USERFAULTFD_TESTS to skip): {e}")
+        }
+    }
+}
+
+#[test]
+fn test_lazy() {
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let len = 16 * page_size + 100;
+    let Some((vmem, loaded)) = new_lazy(len) else {
+        return;
+    };
+    let h = vmem::Handle::from_vmem(vmem);
+    assert_eq!(h.backend_kind(), BackendKind::Lazy);
+    assert_eq!(h.len(), len);
+    assert_eq!(h.capacity(), 17 * page_size);
+    assert!(loaded.lock().unwrap().is_empty());
+
+    // Only the pages that are touched are loaded
+    let vs = vmem::Segment::from_handle(h)
+        .try_split(&io_vec::IoVec::from_chunk_size(len, 4 * page_size))
+        .unwrap();
+    assert_eq!(vs[1][page_size + 7], ((5 * page_size + 7) % 251) as u8);
+    assert_eq!(*loaded.lock().unwrap(), vec![(5 * page_size, page_size)]);
+    assert_eq!(vs[1][page_size + 8], ((5 * page_size + 8) % 251) as u8);
+    assert_eq!(loaded.lock().unwrap().len(), 1);
+
+    // The last page is only partially backed by the loader
+    assert_eq!(vs[4][99], ((16 * page_size + 99) % 251) as u8);
+    assert_eq!(loaded.lock().unwrap()[1], (16 * page_size, 100));
+}
+
+#[test]
+fn test_lazy_write() {
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let len = 4 * page_size;
+    let Some((vmem, loaded)) = new_lazy(len) else {
+        return;
+    };
+
+    // Writes to pages that haven't been loaded yet apply on top of the loaded contents
+    let mut sm = vmem::SegmentMut::from_handle_mut(vmem::HandleMut::try_from_vmem(vmem).unwrap());
+    sm[10] = 0xff;
+    assert_eq!(*loaded.lock().unwrap(), vec![(0, page_size)]);
+    assert!(sm
+        .iter()
+        .enumerate()
+        .all(|(i, &b)| i == 10 && b == 0xff || b == (i % 251) as u8));
+    assert_eq!(loaded.lock().unwrap().len(), 4);
+}
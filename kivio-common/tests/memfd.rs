@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::{FromRawFd, IntoRawFd};
+use std::os::unix::net::UnixStream;
+
+use kivio_common::err::Error;
+use kivio_common::{
+    io_vec, memfd, BackendKind, FdSegment, FdSegmentMut, Handle, HandleMut, Segment, SegmentMut,
+};
+
+#[test]
+fn test_memfd_send_recv() {
+    let hm = memfd::HandleMut::new("kivio-test", 8).unwrap();
+    assert_eq!(hm.backend_kind(), BackendKind::Memfd);
+    let mut sm = memfd::SegmentMut::from_handle_mut(hm);
+    sm.copy_from_slice(b"abcdefgh");
+    let hm = memfd::HandleMut::try_from_segment_mut(sm).unwrap();
+
+    // Sealing makes the memory read-only for everyone
+    let h = hm.seal().unwrap();
+    assert!(h.is_sealed());
+
+    let (tx, rx) = UnixStream::pair().unwrap();
+    h.send(&tx).unwrap();
+    let received = memfd::Handle::recv(&rx).unwrap();
+    assert!(received.is_sealed());
+    assert_ne!(received.fd(), h.fd());
+    assert_eq!(received.len(), 8);
+
+    // The receiving end can't get write access
+    let received = memfd::HandleMut::try_from_handle(received).err().unwrap().1;
+
+    // Segments can be accessed through their pointers as well as the file descriptor
+    let vs = memfd::Segment::from_handle(received)
+        .try_split(&io_vec::IoVec::from_chunk_size(8, 3))
+        .unwrap();
+    assert_eq!(&vs[1][..], b"def");
+    let (fd, offset, len) = vs[1].fd_offset_len();
+    assert_eq!((offset, len), (3, 3));
+    let file = unsafe { std::fs::File::from_raw_fd(fd) };
+    let mut buf = [0u8; 3];
+    file.read_exact_at(&mut buf, offset as u64).unwrap();
+    assert_eq!(&buf, b"def");
+    // The fd is owned by the Vmem
+    let _ = file.into_raw_fd();
+}
+
+#[test]
+fn test_memfd_seal_failure() {
+    let hm = memfd::HandleMut::new("kivio-test", 8).unwrap();
+    let sm = memfd::SegmentMut::from_handle_mut(hm);
+    let (fd, _, len) = sm.fd_offset_len();
+
+    // Another writable mapping of the memfd prevents sealing
+    let other = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            fd,
+            0,
+        )
+    };
+    assert_ne!(other, libc::MAP_FAILED);
+    let hm = memfd::HandleMut::try_from_segment_mut(sm).unwrap();
+    let (e, unsealed) = hm.seal().unwrap_err();
+    assert!(matches!(e, Error::SystemCallFailed { .. }));
+    let memfd::Unsealed::HandleMut(hm) = unsealed else {
+        panic!("Mapping the memfd writable again failed");
+    };
+
+    // The HandleMut is still writable
+    let mut sm = memfd::SegmentMut::from_handle_mut(hm);
+    sm.copy_from_slice(b"abcdefgh");
+    assert_eq!(unsafe { *(other as *const u8) }, b'a');
+
+    unsafe { libc::munmap(other, len) };
+    let hm = memfd::HandleMut::try_from_segment_mut(sm).unwrap();
+    let h = hm.seal().unwrap();
+    assert!(h.is_sealed());
+    assert_eq!(&memfd::Segment::from_handle(h)[..], b"abcdefgh");
+}
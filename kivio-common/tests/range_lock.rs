@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::fs::{File, OpenOptions};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use kivio_common::err::Error;
+use kivio_common::io_vec::{ByteRange, IoVec};
+use kivio_common::vmem::LockMode;
+use kivio_common::{memfd, vmem, FdSegment, FdSegmentMut, Handle, Segment, SegmentMut};
+
+#[test]
+fn test_range_lock() {
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::new_vec_u8(20)).unwrap();
+    let vsm = vmem::SegmentMut::from_handle_mut(hm)
+        .try_split(&IoVec::from_chunk_size(20, 10))
+        .unwrap();
+    let r = |start, end| ByteRange::new_usize(start, end).unwrap();
+
+    // Ranges are relative to the SegmentMut, locks apply to the whole Vmem
+    let guard = vsm[0].lock_range(&r(5, 10), LockMode::Exclusive).unwrap();
+    assert_eq!(guard.len(), 5);
+    assert!(matches!(
+        vsm[0].try_lock_range(&r(8, 9), LockMode::Shared),
+        Err(Error::RangeLocked { start: 8, end: 9 })
+    ));
+    let shared1 = vsm[1].try_lock_range(&r(0, 5), LockMode::Shared).unwrap();
+    let shared2 = vsm[1].try_lock_range(&r(2, 8), LockMode::Shared).unwrap();
+    assert!(matches!(
+        vsm[1].try_lock_range(&r(4, 6), LockMode::Exclusive),
+        Err(Error::RangeLocked { start: 14, end: 16 })
+    ));
+    drop((shared1, shared2));
+    drop(
+        vsm[1]
+            .try_lock_range(&r(4, 6), LockMode::Exclusive)
+            .unwrap(),
+    );
+
+    // Waiting for a conflicting lock to be released
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|s| {
+        s.spawn(|| {
+            let _guard = vsm[0].lock_range(&r(0, 6), LockMode::Shared).unwrap();
+            tx.send(()).unwrap();
+        });
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+        drop(guard);
+        rx.recv().unwrap();
+    });
+}
+
+#[test]
+fn test_range_lock_mut() {
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::new_vec_u8(20)).unwrap();
+    let mut sm = vmem::SegmentMut::from_handle_mut(hm);
+
+    // The guard gives access to exactly the locked range
+    let mut guard = sm
+        .lock_range_mut(&ByteRange::new_usize(5, 10).unwrap())
+        .unwrap();
+    assert_eq!(guard.len(), 5);
+    guard.copy_from_slice(b"abcde");
+    drop(guard);
+    assert_eq!(&sm[..], b"\0\0\0\0\0abcde\0\0\0\0\0\0\0\0\0\0");
+    let guard = sm
+        .try_lock_range(&ByteRange::new_usize(7, 9).unwrap(), LockMode::Shared)
+        .unwrap();
+    assert_eq!(&guard[..], b"cd");
+}
+
+// Locks the range [start, end) through a separate open file description, like another process
+// would have, returns the result of fcntl()
+fn other_lock(other: &File, lock_type: libc::c_int, start: usize, end: usize) -> libc::c_int {
+    let mut flock = unsafe { std::mem::zeroed::<libc::flock>() };
+    flock.l_type = lock_type as libc::c_short;
+    flock.l_whence = libc::SEEK_SET as libc::c_short;
+    flock.l_start = start as libc::off_t;
+    flock.l_len = (end - start) as libc::off_t;
+    unsafe { libc::fcntl(other.as_raw_fd(), libc::F_OFD_SETLK, &mut flock) }
+}
+
+fn open_other(fd: RawFd) -> File {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(format!("/proc/self/fd/{}", fd))
+        .unwrap()
+}
+
+#[test]
+fn test_range_lock_memfd() {
+    let sm = memfd::SegmentMut::from_handle_mut(memfd::HandleMut::new("kivio-test", 4096).unwrap());
+    let (fd, _, _) = sm.fd_offset_len();
+    let other = open_other(fd);
+    let other_lock = |lock_type| other_lock(&other, lock_type, 100, 110);
+
+    let guard = sm
+        .lock_range(
+            &ByteRange::new_usize(100, 200).unwrap(),
+            LockMode::Exclusive,
+        )
+        .unwrap();
+    assert_ne!(other_lock(libc::F_RDLCK), 0);
+    drop(guard);
+    assert_eq!(other_lock(libc::F_WRLCK), 0);
+
+    // ... and the other way around
+    assert!(matches!(
+        sm.try_lock_range(&ByteRange::new_usize(0, 101).unwrap(), LockMode::Shared),
+        Err(Error::RangeLocked { start: 0, end: 101 })
+    ));
+    assert_eq!(other_lock(libc::F_UNLCK), 0);
+    drop(
+        sm.try_lock_range(&ByteRange::new_usize(0, 101).unwrap(), LockMode::Shared)
+            .unwrap(),
+    );
+}
+
+#[test]
+fn test_range_lock_memfd_mut() {
+    let mut sm =
+        memfd::SegmentMut::from_handle_mut(memfd::HandleMut::new("kivio-test", 4096).unwrap());
+    let (fd, _, _) = sm.fd_offset_len();
+    let other = open_other(fd);
+
+    let mut guard = sm
+        .lock_range_mut(&ByteRange::new_usize(100, 200).unwrap())
+        .unwrap();
+    guard.fill(1);
+    assert_ne!(other_lock(&other, libc::F_RDLCK, 150, 151), 0);
+    drop(guard);
+    assert_eq!(other_lock(&other, libc::F_WRLCK, 100, 200), 0);
+    assert!(matches!(
+        sm.try_lock_range_mut(&ByteRange::new_usize(150, 250).unwrap()),
+        Err(Error::RangeLocked {
+            start: 150,
+            end: 250
+        })
+    ));
+    assert!(sm[100..200].iter().all(|&b| b == 1));
+}
+
+#[test]
+fn test_range_lock_memfd_overlapping() {
+    let hm = memfd::HandleMut::new("kivio-test", 4096).unwrap();
+    let s = memfd::Segment::from_handle(memfd::Handle::from_handle_mut(hm));
+    let (fd, _, _) = s.fd_offset_len();
+    let other = open_other(fd);
+    let r = |start, end| ByteRange::new_usize(start, end).unwrap();
+
+    // fcntl() merges the overlapping locks, releasing one must keep the range of the other locked
+    let shared1 = s.lock_range(&r(0, 10), LockMode::Shared).unwrap();
+    let shared2 = s.lock_range(&r(5, 15), LockMode::Shared).unwrap();
+    drop(shared1);
+    assert_ne!(other_lock(&other, libc::F_WRLCK, 5, 10), 0);
+    assert_ne!(other_lock(&other, libc::F_WRLCK, 10, 15), 0);
+    assert_eq!(other_lock(&other, libc::F_WRLCK, 0, 5), 0);
+    assert_eq!(other_lock(&other, libc::F_UNLCK, 0, 5), 0);
+    drop(shared2);
+    assert_eq!(other_lock(&other, libc::F_WRLCK, 0, 15), 0);
+}
+
+#[test]
+fn test_range_lock_memfd_send_recv() {
+    let hm = memfd::HandleMut::new("kivio-test", 4096).unwrap();
+    let h = memfd::Handle::from_handle_mut(hm);
+    let (tx, rx) = UnixStream::pair().unwrap();
+    h.send(&tx).unwrap();
+    let received = memfd::Segment::from_handle(memfd::Handle::recv(&rx).unwrap());
+    let s = memfd::Segment::from_handle(h);
+    let r = |start, end| ByteRange::new_usize(start, end).unwrap();
+
+    // The received memfd has an open file description of its own, so the locks contend like those
+    // of two processes
+    let guard = received.lock_range(&r(0, 10), LockMode::Shared).unwrap();
+    assert!(matches!(
+        s.try_lock_range(&r(5, 6), LockMode::Exclusive),
+        Err(Error::RangeLocked { start: 5, end: 6 })
+    ));
+    drop(s.try_lock_range(&r(5, 6), LockMode::Shared).unwrap());
+    drop(guard);
+    let guard = s.lock_range(&r(0, 10), LockMode::Exclusive).unwrap();
+    assert!(matches!(
+        received.try_lock_range(&r(9, 20), LockMode::Shared),
+        Err(Error::RangeLocked { start: 9, end: 20 })
+    ));
+    drop(guard);
+    drop(
+        received
+            .try_lock_range(&r(9, 20), LockMode::Exclusive)
+            .unwrap(),
+    );
+}
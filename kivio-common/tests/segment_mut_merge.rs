@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use kivio_common::err::Error;
+use kivio_common::{io_vec, vmem, SegmentMut};
+
+#[test]
+fn test_merge_partial_vec_segment_mut() {
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::new_vec_u8(4)).unwrap();
+    let mut vsm = vmem::SegmentMut::from_handle_mut(hm)
+        .try_split(&io_vec::IoVec::from_chunk_size(4, 1))
+        .unwrap();
+    vsm[3][0] = b'd';
+
+    // Merging needs all the SegmentMuts of the Vmem, otherwise they are handed back
+    let last = vsm.pop().unwrap();
+    let (e, mut vsm) = vmem::SegmentMut::try_from_vec_segment_mut(vsm)
+        .err()
+        .unwrap();
+    assert!(matches!(e, Error::ConversionFailed { .. }));
+    assert_eq!(vsm.len(), 3);
+
+    vsm.push(last);
+    let sm = vmem::SegmentMut::try_from_vec_segment_mut(vsm).unwrap();
+    assert_eq!(sm.len(), 4);
+    assert_eq!(sm[3], b'd');
+}
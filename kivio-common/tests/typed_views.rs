@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use kivio_common::err::Error;
+use kivio_common::{io_vec, vmem, Handle, HandleMut, Segment, SegmentMut};
+
+#[test]
+fn test_typed_views() {
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::new_aligned(64, 8).unwrap()).unwrap();
+    let mut sm = vmem::SegmentMut::from_handle_mut(hm);
+    for (i, x) in sm.as_mut_slice::<f32>().unwrap().iter_mut().enumerate() {
+        *x = i as f32;
+    }
+    assert_eq!(sm.as_slice::<u64>().unwrap().len(), 8);
+    assert_eq!(sm.as_slice::<[f32; 4]>().unwrap()[1], [4.0, 5.0, 6.0, 7.0]);
+
+    // Splitting in element units
+    let iov = io_vec::IoVec::from_element_chunk_size::<f32>(16, 5);
+    let vsm = sm.try_split(&iov).unwrap();
+    assert_eq!(
+        vsm[1].as_slice::<f32>().unwrap(),
+        &[5.0, 6.0, 7.0, 8.0, 9.0]
+    );
+    assert_eq!(vsm[3].as_slice::<f32>().unwrap(), &[15.0]);
+
+    // The second SegmentMut starts at byte 20, which is not aligned for u64
+    assert!(matches!(
+        vsm[1].as_slice::<u64>(),
+        Err(Error::MisalignedTypedView { align: 8, .. })
+    ));
+    // ... and 20 bytes are not a whole number of u64s
+    assert!(matches!(
+        vsm[0].as_slice::<u64>(),
+        Err(Error::InvalidTypedViewLength {
+            size: 8,
+            len: 20,
+            ..
+        })
+    ));
+
+    let sm = vmem::SegmentMut::try_from_vec_segment_mut(vsm).unwrap();
+    let h = vmem::Handle::from_handle_mut(vmem::HandleMut::try_from_segment_mut(sm).unwrap());
+    let iov =
+        io_vec::IoVec::from_vec_byte_range(vec![
+            io_vec::ByteRange::new_elements::<u64>(2, 4).unwrap()
+        ]);
+    let vs = vmem::Segment::from_handle(h).try_split(&iov).unwrap();
+    assert_eq!(vs[0].as_slice::<f32>().unwrap(), &[4.0, 5.0, 6.0, 7.0]);
+}
+
+#[test]
+fn test_element_range_overflow() {
+    assert!(matches!(
+        io_vec::ByteRange::new_elements::<u64>(0, usize::MAX / 4),
+        Err(Error::ElementRangeOverflow {
+            start: 0,
+            size: 8,
+            ..
+        })
+    ));
+    assert!(matches!(
+        io_vec::ByteRange::new_elements::<u64>(0, (i64::MAX / 8 + 1) as usize),
+        Err(Error::ElementRangeOverflow { .. })
+    ));
+    assert!(matches!(
+        io_vec::ByteRange::new_elements::<u8>(usize::MAX, 0),
+        Err(Error::ElementRangeOverflow {
+            start: usize::MAX,
+            end: 0,
+            size: 1
+        })
+    ));
+}
+
+#[test]
+#[should_panic(expected = "overflows")]
+fn test_element_chunk_size_overflow() {
+    io_vec::IoVec::from_element_chunk_size::<u64>(usize::MAX / 4, 1);
+}
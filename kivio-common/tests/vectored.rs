@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+
+use kivio_common::vectored::{self, Progress};
+use kivio_common::{io_vec, vmem, Segment, SegmentMut};
+
+#[test]
+fn test_vectored() {
+    let (mut tx, mut rx) = UnixStream::pair().unwrap();
+
+    // Gather from Segments
+    let h = vmem::Handle::from_vmem(vmem::Vmem::from_static_u8(b"abcdefghij"));
+    let iov = io_vec::IoVec::from_chunk_size(10, 3);
+    let vs = vmem::Segment::from_handle(h).try_split(&iov).unwrap();
+    assert_eq!(vectored::io_slices(&vs).len(), 4);
+    let mut progress = Progress::new(&vs);
+    while !progress.is_complete() {
+        // Zero would mean that the writer doesn't accept any more data
+        assert!(progress.write_to(&mut tx, &vs).unwrap() > 0);
+    }
+    assert_eq!(progress.completed(), 4);
+    drop(tx);
+
+    // ... which is what a full writer does, so loops have to stop on zero
+    let mut buf = [0u8; 4];
+    let mut writer = &mut buf[..];
+    let mut progress = Progress::new(&vs);
+    while progress.write_to(&mut writer, &vs).unwrap() > 0 {}
+    assert_eq!(progress.done(), 4);
+    assert!(!progress.is_complete());
+    assert_eq!(&buf, b"abcd");
+
+    // Scatter into SegmentMuts, using readers that only fill the first buffer (like the default
+    // read_vectored()) to provoke short reads
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::new_vec_u8(12)).unwrap();
+    let iov = io_vec::IoVec::from_chunk_size(12, 5);
+    let mut vsm = vmem::SegmentMut::from_handle_mut(hm)
+        .try_split(&iov)
+        .unwrap();
+    let mut progress = Progress::new(&vsm);
+    let mut reader = (&mut rx).take(4);
+    assert_eq!(progress.read_from(&mut reader, &mut vsm).unwrap(), 4);
+    assert_eq!(progress.completed(), 0);
+    assert_eq!(
+        progress.completed_ranges(&iov, 12).unwrap(),
+        vec![io_vec::ByteRange::new_usize(0, 4).unwrap()]
+    );
+
+    let mut reader = (&mut rx).take(4);
+    assert_eq!(progress.read_from(&mut reader, &mut vsm).unwrap(), 1);
+    assert_eq!(progress.completed(), 1);
+    assert_eq!(progress.read_from(&mut reader, &mut vsm).unwrap(), 3);
+    assert_eq!(
+        progress.completed_ranges(&iov, 12).unwrap(),
+        vec![
+            io_vec::ByteRange::new_usize(0, 5).unwrap(),
+            io_vec::ByteRange::new_usize(5, 8).unwrap()
+        ]
+    );
+
+    // Until EOF, which leaves the last SegmentMut incomplete
+    while progress.read_from(&mut rx, &mut vsm).unwrap() > 0 {}
+    assert_eq!(progress.done(), 10);
+    assert!(!progress.is_complete());
+    assert_eq!(progress.completed(), 2);
+
+    let sm = vmem::SegmentMut::try_from_vec_segment_mut(vsm).unwrap();
+    assert_eq!(&sm[..], b"abcdefghij\0\0");
+}
+
+#[test]
+fn test_io_slices_mut() {
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::new_vec_u8(6)).unwrap();
+    let mut vsm = vmem::SegmentMut::from_handle_mut(hm)
+        .try_split(&io_vec::IoVec::from_chunk_size(6, 2))
+        .unwrap();
+    let mut data: &[u8] = b"uvwxyz";
+    assert_eq!(
+        data.read_vectored(&mut vectored::io_slices_mut(&mut vsm))
+            .unwrap(),
+        6
+    );
+    let mut out = Vec::new();
+    assert_eq!(out.write_vectored(&vectored::io_slices(&vsm)).unwrap(), 6);
+    assert_eq!(out, b"uvwxyz");
+}
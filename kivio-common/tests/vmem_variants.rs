@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use kivio_common::err::Error;
+use kivio_common::{io_vec, vmem, BackendKind, Handle, HandleMut, Segment, SegmentMut};
+
+#[test]
+fn test_vmem_variants() {
+    let iov = io_vec::IoVec::from_chunk_size(4, 2);
+
+    // Read-only memory can be split without copying but never be written to
+    static DATA: [u8; 4] = *b"abcd";
+    let h = vmem::Handle::from_vmem(vmem::Vmem::from_static_u8(&DATA));
+    assert_eq!(h.backend_kind(), BackendKind::StaticU8);
+    let vs = vmem::Segment::from_handle(h.clone())
+        .try_split(&iov)
+        .unwrap();
+    assert_eq!(&vs[1][..], b"cd");
+    drop(vs);
+    assert!(vmem::HandleMut::try_from_handle(h).is_err());
+
+    // ... and the Vmem is handed back when trying anyway
+    let (e, v) = vmem::HandleMut::try_from_vmem(vmem::Vmem::from_static_u8(&DATA)).unwrap_err();
+    assert!(matches!(e, Error::ConversionFailed { .. }));
+    assert_eq!(v.backend_kind(), BackendKind::StaticU8);
+
+    // Foreign memory is released through the given drop function
+    let released = Arc::new(AtomicBool::new(false));
+    let released_clone = released.clone();
+    let b = Box::into_raw(vec![0u8; 4].into_boxed_slice()) as *mut u8;
+    let f = unsafe {
+        vmem::Foreign::new(b, 4, move |p, len| {
+            drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(p, len)));
+            released_clone.store(true, Ordering::SeqCst);
+        })
+    };
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::from_foreign(f)).unwrap();
+    let mut vsm = vmem::SegmentMut::from_handle_mut(hm)
+        .try_split(&iov)
+        .unwrap();
+    vsm[1][1] = b'x';
+    let sm = vmem::SegmentMut::try_from_vec_segment_mut(vsm).unwrap();
+    assert_eq!(&sm[..], b"\0\0\0x");
+    assert!(!released.load(Ordering::SeqCst));
+    drop(sm);
+    assert!(released.load(Ordering::SeqCst));
+}
+
+#[test]
+#[should_panic(expected = "read-only")]
+fn test_vmem_read_only_handle_mut() {
+    static DATA: [u8; 4] = *b"abcd";
+    vmem::HandleMut::from_vmem(vmem::Vmem::from_static_u8(&DATA));
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_vmem_bytes() {
+    let h = vmem::Handle::from_vmem(vmem::Vmem::from_bytes(bytes::Bytes::from_static(b"abcd")));
+    assert_eq!(h.backend_kind(), BackendKind::Bytes);
+    let s = vmem::Segment::from_handle(h);
+    assert_eq!(&s[..], b"abcd");
+
+    let hm = vmem::HandleMut::try_from_vmem(vmem::Vmem::from_bytes_mut(bytes::BytesMut::zeroed(4)))
+        .unwrap();
+    let mut sm = vmem::SegmentMut::from_handle_mut(hm);
+    sm[0] = b'a';
+    assert_eq!(&sm[..], b"a\0\0\0");
+}
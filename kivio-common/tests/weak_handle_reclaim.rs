@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: PolyForm-Noncommercial-1.0.0
+
+use std::sync::mpsc::channel;
+
+use kivio_common::{io_vec, vmem, Handle, HandleMut, Segment, WeakHandle};
+
+#[test]
+fn test_weak_handle_reclaim() {
+    let (tx, rx) = channel();
+    let hm = vmem::HandleMut::try_from_vmem_with_reclaim(
+        vmem::Vmem::new_vec_u8(4),
+        vmem::Reclaim::Channel(tx),
+    )
+    .unwrap();
+
+    // Converting back and forth must not trigger the reclaim action
+    let h = vmem::Handle::from_handle_mut(hm);
+    let hm = vmem::HandleMut::try_from_handle(h).unwrap();
+    let h = vmem::Handle::from_handle_mut(hm);
+    assert!(rx.try_recv().is_err());
+
+    let w = h.downgrade();
+    assert_eq!(w.outstanding_refs(), 1);
+
+    let vs = vmem::Segment::from_handle(h)
+        .try_split(&io_vec::IoVec::from_chunk_size(4, 2))
+        .unwrap();
+    assert_eq!(w.outstanding_refs(), 2);
+
+    // The weak handle can be upgraded as long as a reader is around
+    let h = w.upgrade().unwrap();
+    assert_eq!(h.outstanding_refs(), 2);
+    drop(h);
+    drop(vs);
+
+    // Once the last reader is gone the Vmem is handed back and can't be upgraded anymore
+    let v = rx.try_recv().unwrap();
+    assert_eq!(v.len(), 4);
+    assert!(w.upgrade().is_none());
+    assert_eq!(w.outstanding_refs(), 0);
+}